@@ -15,27 +15,105 @@
  * along with this program. If not, see <https://www.gnu.org/licenses/>.
  */
 
-use blacknet_compat::{XDGDirectories, mode};
+use blacknet_compat::{Config, XDGDirectories, mode};
+use blacknet_json_rpc::{COOKIE_FILENAME, Settings};
 use clap::Parser;
+use data_encoding::BASE64;
+use serde_json::{Map, Value};
 use std::error::Error;
+use std::fs::read_to_string;
 use std::process::ExitCode;
+use ureq::Agent;
 
 #[derive(Parser)]
 #[command(version)]
 #[command(about = "Blacknet RPC client", long_about = None)]
 struct Cli {
-    /// RPC command.
+    /// RPC command, for example wallet/create.
     command: String,
-    /// Arguments for the command.
+    /// Arguments for the command: key=value for strings, key:=value for other JSON values.
     args: Vec<String>,
 }
 
-#[expect(unused_variables)]
 fn cli() -> Result<(), Box<dyn Error>> {
     let cli = Cli::parse();
     let mode = mode()?;
     let dirs = XDGDirectories::new(mode.subdirectory())?;
-    todo!();
+    // only the RPC section is of interest here
+    let mut config = Config::new();
+    config.load(dirs.config())?;
+    let mut settings = Settings::default(&mode);
+    settings.configure(&mut config)?;
+
+    // configured credentials work without access to the state of daemon
+    let credentials = if !settings.user.is_empty() {
+        format!("{}:{}", settings.user, settings.password)
+    } else {
+        let cookie_path = dirs.state().join(COOKIE_FILENAME);
+        let cookie = read_to_string(&cookie_path)
+            .map_err(|err| format!("Can't read {}: {err}", cookie_path.display()))?;
+        cookie.trim().to_owned()
+    };
+    let body = Value::Object(parameters(&cli.args)?).to_string();
+    let path = format!("/api/v3/{}", cli.command);
+    let (status, response) = post(&settings, &path, &credentials, body)?;
+    if (200..300).contains(&status) {
+        if !response.is_empty() {
+            println!("{response}");
+        }
+        Ok(())
+    } else {
+        Err(format!("HTTP {status} {response}").into())
+    }
+}
+
+fn parameters(args: &[String]) -> Result<Map<String, Value>, Box<dyn Error>> {
+    let mut parameters = Map::new();
+    for arg in args {
+        let Some((key, value)) = arg.split_once('=') else {
+            return Err(format!("Invalid argument {arg}").into());
+        };
+        let (key, value) = match key.strip_suffix(':') {
+            Some(key) => (
+                key,
+                serde_json::from_str(value)
+                    .map_err(|err| format!("Invalid JSON value of {key}: {err}"))?,
+            ),
+            None => (key, Value::String(value.to_owned())),
+        };
+        parameters.insert(key.to_owned(), value);
+    }
+    Ok(parameters)
+}
+
+fn post(
+    settings: &Settings,
+    path: &str,
+    credentials: &str,
+    body: String,
+) -> Result<(u16, String), Box<dyn Error>> {
+    let host = if settings.host.contains(':') {
+        format!("[{}]", settings.host)
+    } else {
+        settings.host.clone()
+    };
+    // error status has a message in body
+    let agent: Agent = Agent::config_builder()
+        .http_status_as_error(false)
+        .build()
+        .into();
+    let mut response = agent
+        .post(format!("http://{host}:{}{path}", settings.port))
+        .header(
+            "Authorization",
+            format!("Basic {}", BASE64.encode(credentials.as_bytes())),
+        )
+        .header("Content-Type", "application/json")
+        .send(body)
+        .map_err(|err| format!("Can't connect to {host}:{}: {err}", settings.port))?;
+    let status = response.status().as_u16();
+    let body = response.body_mut().read_to_string()?;
+    Ok((status, body))
 }
 
 fn main() -> ExitCode {
@@ -0,0 +1,215 @@
+/*
+ * Copyright (c) 2026 Pavel Vasin
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Lesser General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use core::fmt::{Display, Formatter, Result as FmtResult};
+use core::str::FromStr;
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fs::read_to_string;
+use std::io::ErrorKind;
+use std::path::Path;
+
+pub const CONFIG_FILENAME: &str = "blacknet.toml";
+
+/**
+ * Flat subset of TOML: `key = value` pairs, optionally grouped under `[section]` headers,
+ * which become part of the key as `section.key`. Later layers override earlier ones.
+ */
+#[derive(Debug, Default)]
+pub struct Config {
+    values: BTreeMap<String, String>,
+}
+
+impl Config {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /**
+     * Missing file is not an error
+     */
+    pub fn load(&mut self, dir: &Path) -> Result<(), Box<dyn Error>> {
+        let path = dir.join(CONFIG_FILENAME);
+        let string = match read_to_string(&path) {
+            Ok(string) => string,
+            Err(err) => {
+                if err.kind() == ErrorKind::NotFound {
+                    return Ok(());
+                } else {
+                    return Err(format!("Can't read {}: {err}", path.display()).into());
+                }
+            }
+        };
+        self.parse(&string)?;
+        Ok(())
+    }
+
+    pub fn parse(&mut self, string: &str) -> Result<(), ConfigError> {
+        let mut section = String::new();
+        let mut keys = BTreeSet::new();
+        for (n, line) in string.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(header) = line.strip_prefix('[') {
+                let Some(name) = header.strip_suffix(']').map(str::trim) else {
+                    return Err(ConfigError::Syntax { line: n + 1 });
+                };
+                if !is_key(name) {
+                    return Err(ConfigError::Syntax { line: n + 1 });
+                }
+                section = format!("{name}.");
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(ConfigError::Syntax { line: n + 1 });
+            };
+            let key = key.trim();
+            if !is_key(key) {
+                return Err(ConfigError::Syntax { line: n + 1 });
+            }
+            let key = format!("{section}{key}");
+            if !keys.insert(key.clone()) {
+                return Err(ConfigError::Duplicate { key });
+            }
+            let Some(value) = unquote(value.trim()) else {
+                return Err(ConfigError::Value {
+                    key,
+                    value: value.trim().to_owned(),
+                });
+            };
+            self.values.insert(key, value);
+        }
+        Ok(())
+    }
+
+    /**
+     * Command line overrides in the form of `--key=value`, or `--key` for `true`
+     */
+    pub fn override_args(&mut self, args: impl Iterator<Item = String>) -> Result<(), ConfigError> {
+        for arg in args {
+            let Some(arg) = arg.strip_prefix("--") else {
+                return Err(ConfigError::Argument { arg: arg.clone() });
+            };
+            let (key, value) = arg.split_once('=').unwrap_or((arg, "true"));
+            if !is_key(key) {
+                return Err(ConfigError::Argument {
+                    arg: arg.to_owned(),
+                });
+            }
+            self.values.insert(key.to_owned(), value.to_owned());
+        }
+        Ok(())
+    }
+
+    /**
+     * Consumes the key if present
+     */
+    pub fn take<T: FromStr>(&mut self, key: &str, target: &mut T) -> Result<(), ConfigError> {
+        self.take_if(key, target, |_| true)
+    }
+
+    pub fn take_if<T: FromStr>(
+        &mut self,
+        key: &str,
+        target: &mut T,
+        predicate: impl FnOnce(&T) -> bool,
+    ) -> Result<(), ConfigError> {
+        if let Some(value) = self.values.remove(key) {
+            match value.parse::<T>() {
+                Ok(parsed) if predicate(&parsed) => *target = parsed,
+                _ => {
+                    return Err(ConfigError::Value {
+                        key: key.to_owned(),
+                        value,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /**
+     * Fails on keys that no one has taken
+     */
+    pub fn finish(self) -> Result<(), ConfigError> {
+        match self.values.into_keys().next() {
+            Some(key) => Err(ConfigError::Unknown { key }),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Syntax { line: usize },
+    Argument { arg: String },
+    Value { key: String, value: String },
+    Unknown { key: String },
+    Duplicate { key: String },
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            ConfigError::Syntax { line } => {
+                write!(f, "Invalid syntax at line {line} of {CONFIG_FILENAME}")
+            }
+            ConfigError::Argument { arg } => write!(f, "Invalid argument {arg}"),
+            ConfigError::Value { key, value } => write!(f, "Invalid value {value} of {key}"),
+            ConfigError::Unknown { key } => write!(f, "Unknown setting {key}"),
+            ConfigError::Duplicate { key } => {
+                write!(f, "Duplicate setting {key} in {CONFIG_FILENAME}")
+            }
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+fn is_key(string: &str) -> bool {
+    !string.is_empty()
+        && string
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-' | '.'))
+}
+
+fn unquote(value: &str) -> Option<String> {
+    if let Some(quoted) = value.strip_prefix('"') {
+        let (string, rest) = quoted.split_once('"')?;
+        let rest = rest.trim_start();
+        if string.contains('\\') || !(rest.is_empty() || rest.starts_with('#')) {
+            return None;
+        }
+        Some(string.to_owned())
+    } else {
+        let bare = match value.split_once('#') {
+            Some((bare, _)) => bare.trim_end(),
+            None => value,
+        };
+        if bare.is_empty() {
+            return None;
+        }
+        // TOML allows underscores between digits
+        if bare.starts_with(|ch: char| ch.is_ascii_digit()) {
+            Some(bare.replace('_', ""))
+        } else {
+            Some(bare.to_owned())
+        }
+    }
+}
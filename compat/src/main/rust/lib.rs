@@ -15,6 +15,7 @@
  * along with this program. If not, see <https://www.gnu.org/licenses/>.
  */
 
+mod config;
 mod errno;
 mod getentropy;
 mod getuid;
@@ -27,6 +28,7 @@ mod ulimit;
 mod uname;
 mod xdgdirectories;
 
+pub use config::*;
 pub use errno::*;
 pub use getentropy::*;
 pub use getuid::*;
@@ -18,10 +18,11 @@
 use crate::magic::XDG_SUBDIRECTORY;
 use std::env::VarError;
 use std::error::Error;
-use std::fs::DirBuilder;
+use std::fs::{DirBuilder, remove_dir_all};
 #[cfg(target_family = "unix")]
 use std::os::unix::fs::DirBuilderExt;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 // https://specifications.freedesktop.org/basedir-spec/0.8/
 
@@ -31,6 +32,7 @@ pub struct XDGDirectories {
     config: PathBuf,
     data: PathBuf,
     state: PathBuf,
+    temporary: bool,
 }
 
 impl XDGDirectories {
@@ -45,6 +47,26 @@ impl XDGDirectories {
             config,
             data,
             state,
+            temporary: false,
+        })
+    }
+
+    /**
+     * A new directory under the temporary one for all three, that is removed on drop
+     */
+    pub fn temporary(name: &str) -> Result<Self> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let dir = std::env::temp_dir().join(format!(
+            "blacknet-{name}-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        Self::mkdirs(&dir)?;
+        Ok(Self {
+            config: dir.clone(),
+            data: dir.clone(),
+            state: dir,
+            temporary: true,
         })
     }
 
@@ -163,3 +185,11 @@ impl XDGDirectories {
         Ok(dir)
     }
 }
+
+impl Drop for XDGDirectories {
+    fn drop(&mut self) {
+        if self.temporary {
+            let _ = remove_dir_all(&self.data);
+        }
+    }
+}
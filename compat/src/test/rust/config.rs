@@ -0,0 +1,120 @@
+/*
+ * Copyright (c) 2026 Pavel Vasin
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Lesser General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use blacknet_compat::{Config, ConfigError};
+use core::assert_matches;
+
+fn parse(string: &str) -> Result<Config, ConfigError> {
+    let mut config = Config::new();
+    config.parse(string)?;
+    Ok(config)
+}
+
+#[test]
+fn values() {
+    let mut config = parse(
+        r#"
+# comment
+port = 28453 # trailing comment
+db_cache = 1_000_000
+
+[rpc]
+host = "127.0.0.1" # quoted
+user = "with # hash"
+password = bare
+"#,
+    )
+    .unwrap();
+    let mut port = 0u16;
+    let mut db_cache = 0u64;
+    let mut host = String::new();
+    let mut user = String::new();
+    let mut password = String::new();
+    config.take("port", &mut port).unwrap();
+    config.take("db_cache", &mut db_cache).unwrap();
+    config.take("rpc.host", &mut host).unwrap();
+    config.take("rpc.user", &mut user).unwrap();
+    config.take("rpc.password", &mut password).unwrap();
+    config.finish().unwrap();
+    assert_eq!(port, 28453);
+    assert_eq!(db_cache, 1_000_000);
+    assert_eq!(host, "127.0.0.1");
+    assert_eq!(user, "with # hash");
+    assert_eq!(password, "bare");
+}
+
+#[test]
+fn overrides() {
+    let mut config = parse("port = 28453\ntor = true").unwrap();
+    config
+        .override_args(["--port=28454".to_owned(), "--natpmp".to_owned()].into_iter())
+        .unwrap();
+    let mut port = 0u16;
+    let mut tor = false;
+    let mut natpmp = false;
+    config.take("port", &mut port).unwrap();
+    config.take("tor", &mut tor).unwrap();
+    config.take("natpmp", &mut natpmp).unwrap();
+    config.finish().unwrap();
+    assert_eq!(port, 28454);
+    assert!(tor);
+    assert!(natpmp);
+
+    let mut config = Config::new();
+    assert_matches!(
+        config.override_args(["port=1".to_owned()].into_iter()),
+        Err(ConfigError::Argument { .. })
+    );
+}
+
+#[test]
+fn duplicates() {
+    assert_matches!(
+        parse("port = 1\nport = 2"),
+        Err(ConfigError::Duplicate { key }) if key == "port"
+    );
+    assert_matches!(
+        parse("[rpc]\nport = 1\n[rpc]\nport = 2"),
+        Err(ConfigError::Duplicate { key }) if key == "rpc.port"
+    );
+    assert_matches!(parse("port = 1\n[rpc]\nport = 2"), Ok(_));
+}
+
+#[test]
+fn malformed() {
+    assert_matches!(parse("port"), Err(ConfigError::Syntax { line: 1 }));
+    assert_matches!(parse("\n[rpc"), Err(ConfigError::Syntax { line: 2 }));
+    assert_matches!(parse("[r p c]"), Err(ConfigError::Syntax { line: 1 }));
+    assert_matches!(parse("p ort = 1"), Err(ConfigError::Syntax { line: 1 }));
+    assert_matches!(parse("= 1"), Err(ConfigError::Syntax { line: 1 }));
+    assert_matches!(parse("port ="), Err(ConfigError::Value { .. }));
+    assert_matches!(parse("port = # 1"), Err(ConfigError::Value { .. }));
+    assert_matches!(
+        parse(r#"host = "127.0.0.1"#),
+        Err(ConfigError::Value { .. })
+    );
+    assert_matches!(parse(r#"host = "a" "b""#), Err(ConfigError::Value { .. }));
+    assert_matches!(parse(r#"host = "a\"b""#), Err(ConfigError::Value { .. }));
+
+    let mut config = parse("port = 99999\nunknown = 1").unwrap();
+    let mut port = 0u16;
+    assert_matches!(
+        config.take("port", &mut port),
+        Err(ConfigError::Value { .. })
+    );
+    assert_matches!(config.finish(), Err(ConfigError::Unknown { key }) if key == "unknown");
+}
@@ -56,6 +56,18 @@ impl<const BYTES: usize> Blake2b<BYTES> {
         Self::with_params(1, 1, 0, 0, 0, 0, personalization)
     }
 
+    /// Construct new keyed hasher (MAC) with personalization.
+    ///
+    /// Key must be at most 64 bytes.
+    pub fn with_key(key: &[u8], personalization: [u8; 16]) -> Self {
+        assert!(!key.is_empty() && key.len() <= 64, "Invalid key length");
+        let mut hasher = Self::with_params(1, 1, 0, 0, 0, 0, personalization);
+        hasher.state[0] ^= (key.len() as Word) << 8;
+        hasher.buffer[..key.len()].copy_from_slice(key);
+        hasher.position = BLOCK_SIZE;
+        hasher
+    }
+
     pub(super) const fn with_params(
         fanout: u8,
         depth: u8,
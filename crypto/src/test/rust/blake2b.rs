@@ -52,6 +52,28 @@ fn personalization() {
     assert_eq!(hash256, hasher.finalize());
 }
 
+#[test]
+fn key() {
+    let key: [u8; 32] = array::from_fn(|i| i as u8);
+    let personalization: [u8; 16] = array::from_fn(|i| (0x41 + i) as u8);
+    let message: [u8; 4] = array::from_fn(|i| (0x61 + i) as u8);
+    let hash256: [u8; 32] = [
+        0x20, 0xDC, 0xC9, 0xC2, 0xE2, 0x71, 0x48, 0x95, 0x5B, 0x1E, 0x35, 0xC1, 0xA4, 0x45, 0xA3,
+        0xFD, 0x82, 0x87, 0x1F, 0xAC, 0x0E, 0x68, 0x12, 0xFA, 0x12, 0xF8, 0x30, 0x30, 0x61, 0xE1,
+        0x92, 0xE0,
+    ];
+    let mut hasher = Blake2b256::with_key(&key, personalization);
+    hasher.update(message);
+    assert_eq!(hash256, hasher.finalize());
+
+    let empty256: [u8; 32] = [
+        0x4E, 0x51, 0xE7, 0xA9, 0x13, 0xFC, 0x80, 0x13, 0x7D, 0xA5, 0x28, 0x80, 0xFE, 0xCC, 0xA1,
+        0x75, 0xBF, 0x81, 0xE1, 0x17, 0xD5, 0xC6, 0x81, 0x26, 0xDC, 0x27, 0x74, 0x03, 0x35, 0x17,
+        0xEA, 0x0D,
+    ];
+    assert_eq!(empty256, Blake2b256::with_key(&key, [0; 16]).finalize());
+}
+
 #[test]
 fn xof() {
     let message: [u8; 4] = array::from_fn(|i| (0x61 + i) as u8);
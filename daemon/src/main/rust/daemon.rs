@@ -15,10 +15,11 @@
  * along with this program. If not, see <https://www.gnu.org/licenses/>.
  */
 
-use blacknet_compat::{XDGDirectories, mode};
+use blacknet_compat::{Config, XDGDirectories, mode};
 use blacknet_json_rpc::{Settings as RPCSettings, rpc_server};
 use blacknet_log::{LogManager, Strategy};
 use blacknet_network::node::Node;
+use blacknet_network::settings::Settings;
 use std::env::args;
 use std::error::Error;
 use std::process::ExitCode;
@@ -34,6 +35,15 @@ fn daemon() -> Result<(), Box<dyn Error>> {
     }
     let mode = mode()?;
     let dirs = XDGDirectories::new(mode.subdirectory())?;
+    // command line overrides config file
+    let mut config = Config::new();
+    config.load(dirs.config())?;
+    config.override_args(args().skip(1))?;
+    let mut settings = Settings::default(&mode);
+    settings.configure(&mut config)?;
+    let mut rpc_settings = RPCSettings::default(&mode);
+    rpc_settings.configure(&mut config)?;
+    config.finish()?;
     let log_manager = LogManager::new(Strategy::Daemon, dirs.state())?;
     let runtime = tokio::runtime::Builder::new_multi_thread()
         .enable_all()
@@ -45,12 +55,12 @@ fn daemon() -> Result<(), Box<dyn Error>> {
         })
         .build()?;
     let (shutdown_send, mut shutdown_recv) = unbounded_channel::<()>();
-    let rpc_settings = RPCSettings::default(&mode);
-    let node = Node::new(mode, &dirs, &log_manager, &runtime)?;
+    let node = Node::new(mode, &dirs, &log_manager, &runtime, settings)?;
     if rpc_settings.enabled {
         let node = node.clone();
+        let state_dir = dirs.state().to_owned();
         runtime.spawn(async move {
-            rpc_server(rpc_settings, &log_manager, node, shutdown_send).await;
+            rpc_server(rpc_settings, state_dir, &log_manager, node, shutdown_send).await;
         });
     }
     runtime.block_on(async move {
@@ -0,0 +1,139 @@
+/*
+ * Copyright (c) 2026 Pavel Vasin
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Lesser General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::Settings;
+use axum::{
+    extract::{Request, State},
+    http::{HeaderMap, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use blacknet_compat::getentropy;
+use core::error::Error;
+use data_encoding::{BASE64, HEXLOWER};
+use std::fs::{OpenOptions, remove_file};
+use std::io::{ErrorKind, Write};
+#[cfg(target_family = "unix")]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
+use std::sync::Arc;
+
+pub const COOKIE_FILENAME: &str = "rpc.cookie";
+pub const COOKIE_USER: &str = "__cookie__";
+
+#[derive(Clone, Copy, Eq, Ord, PartialEq, PartialOrd)]
+pub enum Permission {
+    ReadOnly,
+    Admin,
+}
+
+/**
+ * HTTP Basic authentication against the cookie file and configured credentials
+ */
+pub struct Auth {
+    credentials: Vec<(String, Permission)>,
+}
+
+impl Auth {
+    /**
+     * Writes a new cookie, so that a local client can authenticate as admin by reading it
+     */
+    pub fn new(settings: &Settings, state_dir: &Path) -> Result<Arc<Self>, Box<dyn Error>> {
+        let mut token = [0u8; 32];
+        getentropy(&mut token)?;
+        let cookie = format!("{COOKIE_USER}:{}", HEXLOWER.encode(&token));
+        write_cookie(&state_dir.join(COOKIE_FILENAME), &cookie)?;
+
+        let mut credentials = vec![(cookie, Permission::Admin)];
+        if !settings.user.is_empty() {
+            credentials.push((
+                format!("{}:{}", settings.user, settings.password),
+                Permission::Admin,
+            ));
+        }
+        if !settings.readonly_user.is_empty() {
+            credentials.push((
+                format!("{}:{}", settings.readonly_user, settings.readonly_password),
+                Permission::ReadOnly,
+            ));
+        }
+        Ok(Arc::new(Self { credentials }))
+    }
+
+    fn permission(&self, headers: &HeaderMap) -> Option<Permission> {
+        let value = headers.get(header::AUTHORIZATION)?.to_str().ok()?;
+        let encoded = value.strip_prefix("Basic ")?;
+        let decoded = BASE64.decode(encoded.trim().as_bytes()).ok()?;
+        // every entry is checked to not leak which one has matched
+        let mut permission = None;
+        for (credential, granted) in self.credentials.iter() {
+            if constant_time_eq(credential.as_bytes(), &decoded) {
+                permission = max_permission(permission, *granted);
+            }
+        }
+        permission
+    }
+}
+
+/**
+ * Middleware for routes that need at least `required` permission
+ */
+pub async fn require(
+    State((auth, required)): State<(Arc<Auth>, Permission)>,
+    request: Request,
+    next: Next,
+) -> Response {
+    match auth.permission(request.headers()) {
+        Some(permission) if permission >= required => next.run(request).await,
+        Some(_) => StatusCode::FORBIDDEN.into_response(),
+        None => (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Basic realm=\"Blacknet RPC\"")],
+        )
+            .into_response(),
+    }
+}
+
+fn write_cookie(path: &Path, cookie: &str) -> Result<(), Box<dyn Error>> {
+    // mode applies only to newly created file
+    match remove_file(path) {
+        Ok(()) => (),
+        Err(err) if err.kind() == ErrorKind::NotFound => (),
+        Err(err) => return Err(format!("Can't remove {}: {err}", path.display()).into()),
+    }
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(target_family = "unix")]
+    options.mode(0o600);
+    let mut file = options
+        .open(path)
+        .map_err(|err| format!("Can't create {}: {err}", path.display()))?;
+    file.write_all(cookie.as_bytes())?;
+    file.sync_all()?;
+    Ok(())
+}
+
+fn max_permission(a: Option<Permission>, b: Permission) -> Option<Permission> {
+    Some(a.map_or(b, |a| a.max(b)))
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
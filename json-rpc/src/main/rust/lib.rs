@@ -15,9 +15,12 @@
  * along with this program. If not, see <https://www.gnu.org/licenses/>.
  */
 
+mod auth;
 mod rpcserver;
 mod settings;
 pub mod v2;
+pub mod v3;
 
+pub use auth::*;
 pub use rpcserver::*;
 pub use settings::*;
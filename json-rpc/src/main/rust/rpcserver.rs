@@ -15,29 +15,77 @@
  * along with this program. If not, see <https://www.gnu.org/licenses/>.
  */
 
-use crate::{Settings, v2};
-use axum::{Router, routing::get};
+use crate::{Auth, Permission, Settings, require, v2, v3};
+use axum::{
+    Router,
+    http::StatusCode,
+    middleware::from_fn_with_state,
+    routing::{get, post},
+};
 use blacknet_log::{LogManager, error, info};
 use blacknet_network::node::Node;
+use std::io;
+use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::net::TcpListener;
+use tokio::net::{TcpListener, lookup_host};
 use tokio::sync::mpsc::UnboundedSender;
 
 pub async fn rpc_server(
     settings: Settings,
+    state_dir: PathBuf,
     log_manager: &LogManager,
     node: Arc<Node>,
     shutdown_send: UnboundedSender<()>,
 ) {
-    let router = Router::new()
+    let addr = format!("{}:{}", settings.host, settings.port);
+    let logger = log_manager.logger("RPCServer").unwrap();
+    match is_loopback(&addr).await {
+        Ok(true) => (),
+        Ok(false) => {
+            if settings.user.is_empty() || settings.password.is_empty() {
+                error!(
+                    logger,
+                    "Refusing to serve RPC at non-loopback {addr} without rpc.user and rpc.password"
+                );
+                panic!();
+            }
+            info!(logger, "Serving RPC at non-loopback {addr}");
+        }
+        Err(err) => {
+            error!(logger, "Can't resolve {addr} because {err}");
+            panic!();
+        }
+    }
+    let auth = match Auth::new(&settings, &state_dir) {
+        Ok(auth) => auth,
+        Err(err) => {
+            error!(logger, "{err}");
+            panic!();
+        }
+    };
+    let shutdown_v3 = shutdown_send.clone();
+    let admin = Router::new()
         .route(
             "/api/shutdown",
             get(|| async move { shutdown_send.send(()).unwrap() }),
         )
-        .merge(v2::routes())
+        .route(
+            "/api/v3/shutdown",
+            post(|| async move {
+                shutdown_v3.send(()).unwrap();
+                StatusCode::NO_CONTENT
+            }),
+        )
+        .merge(v2::admin_routes())
+        .merge(v3::admin_routes())
+        .route_layer(from_fn_with_state(
+            (auth.clone(), Permission::Admin),
+            require,
+        ));
+    let router = v2::routes()
+        .route_layer(from_fn_with_state((auth, Permission::ReadOnly), require))
+        .merge(admin)
         .with_state(node);
-    let addr = format!("{}:{}", settings.host, settings.port);
-    let logger = log_manager.logger("RPCServer").unwrap();
     match TcpListener::bind(&addr).await {
         Ok(listener) => {
             info!(logger, "Serving RPC at {addr}");
@@ -50,3 +98,14 @@ pub async fn rpc_server(
         }
     };
 }
+
+async fn is_loopback(addr: &str) -> io::Result<bool> {
+    let mut resolved = false;
+    for socket_addr in lookup_host(addr).await? {
+        if !socket_addr.ip().is_loopback() {
+            return Ok(false);
+        }
+        resolved = true;
+    }
+    Ok(resolved)
+}
@@ -15,12 +15,17 @@
  * along with this program. If not, see <https://www.gnu.org/licenses/>.
  */
 
-use blacknet_compat::Mode;
+use crate::auth::COOKIE_USER;
+use blacknet_compat::{Config, ConfigError, Mode};
 
 pub struct Settings {
     pub enabled: bool,
     pub host: String,
     pub port: u16,
+    pub user: String,
+    pub password: String,
+    pub readonly_user: String,
+    pub readonly_password: String,
 }
 
 impl Settings {
@@ -29,6 +34,27 @@ impl Settings {
             enabled: true,
             host: "127.0.0.1".to_owned(),
             port: mode.default_rpc_port(),
+            user: String::new(),
+            password: String::new(),
+            readonly_user: String::new(),
+            readonly_password: String::new(),
         }
     }
+
+    pub fn configure(&mut self, config: &mut Config) -> Result<(), ConfigError> {
+        config.take("rpc.enabled", &mut self.enabled)?;
+        config.take_if("rpc.host", &mut self.host, |host| !host.is_empty())?;
+        config.take_if("rpc.port", &mut self.port, |&port| port != 0)?;
+        config.take_if("rpc.user", &mut self.user, |user| is_user(user))?;
+        config.take("rpc.password", &mut self.password)?;
+        config.take_if("rpc.readonly_user", &mut self.readonly_user, |user| {
+            is_user(user)
+        })?;
+        config.take("rpc.readonly_password", &mut self.readonly_password)?;
+        Ok(())
+    }
+}
+
+fn is_user(user: &str) -> bool {
+    !user.contains(':') && user != COOKIE_USER
 }
@@ -0,0 +1,38 @@
+/*
+ * Copyright (c) 2026 Pavel Vasin
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Lesser General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::v2::HashInfo;
+use blacknet_kernel::blake2b::Hash;
+use blacknet_network::addressindex::Key;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+pub struct AddressTxInfo {
+    hash: HashInfo,
+    height: u32,
+    dataIndex: u32,
+}
+
+impl AddressTxInfo {
+    pub fn new(key: Key, hash: Hash) -> Self {
+        Self {
+            hash: hash.into(),
+            height: key.height(),
+            dataIndex: key.data_index(),
+        }
+    }
+}
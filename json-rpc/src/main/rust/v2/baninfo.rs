@@ -0,0 +1,41 @@
+/*
+ * Copyright (c) 2026 Pavel Vasin
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Lesser General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::v2::EndpointInfo;
+use blacknet_network::banlist::Entry;
+use blacknet_network::endpoint::Endpoint;
+use blacknet_time::Milliseconds;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+pub struct BanInfo {
+    address: EndpointInfo,
+    subnet: bool,
+    until: Milliseconds,
+    reason: String,
+}
+
+impl BanInfo {
+    pub fn new(endpoint: Endpoint, subnet: bool, entry: &Entry) -> Self {
+        Self {
+            address: endpoint.into(),
+            subnet,
+            until: entry.until(),
+            reason: entry.reason().to_owned(),
+        }
+    }
+}
@@ -16,7 +16,11 @@
  */
 
 use crate::v2::response::*;
-use crate::v2::{AccountInfo, BlockIndexInfo, BlockInfo, CoinDBInfo, PeerTableInfo};
+use crate::v2::{
+    AccountInfo, AddressTxInfo, BlockIndexInfo, BlockInfo, BlockNotification, CoinDBInfo,
+    PeerTableInfo, Result, TransactionInfo, TxLocationInfo,
+};
+use crate::v3::database::make_bootstrap_impl;
 use axum::{
     Json, Router,
     extract::{Path, State},
@@ -25,12 +29,16 @@ use axum::{
 };
 use blacknet_kernel::blake2b::Hash;
 use blacknet_kernel::proofofstake::DEFAULT_CONFIRMATIONS;
+use blacknet_kernel::transaction::Transaction;
 use blacknet_network::blockdb::Check as BlockDBCheck;
 use blacknet_network::coindb::Check as CoinDBCheck;
 use blacknet_network::node::Node;
-use std::path::absolute;
+use blacknet_serialization::format::from_bytes;
+use blacknet_wallet::address::AddressCodec;
 use std::sync::Arc;
 
+const MAX_PAGE_SIZE: u32 = 100;
+
 async fn peer_table(State(node): State<Arc<Node>>) -> Json<PeerTableInfo> {
     let peer_table = node.peer_table();
     Json(PeerTableInfo::new(peer_table))
@@ -79,19 +87,54 @@ async fn block(
 async fn block_db_check(State(node): State<Arc<Node>>) -> Json<BlockDBCheck> {
     let block_db = node.block_db();
     let coin_db = node.coin_db();
-    Json(block_db.check(coin_db.state()))
+    Json(block_db.check(&coin_db.state()))
 }
 
 async fn block_hash(Path(height): Path<u32>, State(node): State<Arc<Node>>) -> Response<String> {
     let block_db = node.block_db();
     let coin_db = node.coin_db();
-    if let Some(hash) = block_db.hash(height, coin_db.state()) {
+    if let Some(hash) = block_db.hash(height, &coin_db.state()) {
         respond_text(hash.to_string())
     } else {
         respond_error("Block not found")
     }
 }
 
+async fn blocks(
+    Path((height, count)): Path<(u32, Option<u32>)>,
+    State(node): State<Arc<Node>>,
+) -> Response<String> {
+    let block_db = node.block_db();
+    let coin_db = node.coin_db();
+    let count = count.unwrap_or(MAX_PAGE_SIZE).min(MAX_PAGE_SIZE);
+    if count == 0 {
+        return respond_json(&Vec::<BlockNotification>::new());
+    }
+    let start = match block_db.hash(height, &coin_db.state()) {
+        Some(hash) => hash,
+        None => return respond_error("Block not found"),
+    };
+    let mut hashes = vec![start];
+    if count > 1
+        && let Some(next) = block_db.next_block_hashes(start, count as usize - 1)
+    {
+        hashes.extend(next);
+    }
+    let address_codec = node.wallet_db().address_codec();
+    let mut infos = Vec::with_capacity(hashes.len());
+    for (i, hash) in hashes.into_iter().enumerate() {
+        // genesis is not in blocks
+        let Some((block, size)) = block_db.get(hash) else {
+            continue;
+        };
+        match BlockNotification::new(&block, hash, height + i as u32, size as u32, address_codec) {
+            Ok(info) => infos.push(info),
+            Err(err) => return respond_error(format!("Internal error: {err}")),
+        }
+    }
+    respond_json(&infos)
+}
+
 async fn block_index(Path(hash): Path<Hash>, State(node): State<Arc<Node>>) -> Response<String> {
     let block_db = node.block_db();
     if let Some(index) = block_db.index(hash) {
@@ -102,20 +145,15 @@ async fn block_index(Path(hash): Path<Hash>, State(node): State<Arc<Node>>) -> R
 }
 
 async fn make_bootstrap(State(node): State<Arc<Node>>) -> Response<String> {
-    let block_db = node.block_db();
-    let coin_db = node.coin_db();
-    match block_db.export(coin_db.state()) {
-        Some(path) => match absolute(&path) {
-            Ok(path) => respond_text(path.display().to_string()),
-            Err(_) => respond_text(path.display().to_string()),
-        },
-        None => respond_error("Not synchronized"),
+    match make_bootstrap_impl(&node) {
+        Ok(response) => respond_text(response.path),
+        Err(err) => respond_error(err.message()),
     }
 }
 
 async fn coin_db(State(node): State<Arc<Node>>) -> Json<CoinDBInfo> {
     let coin_db = node.coin_db();
-    Json(CoinDBInfo::new(coin_db.state()))
+    Json(CoinDBInfo::new(&coin_db.state()))
 }
 
 async fn coin_db_check(State(node): State<Arc<Node>>) -> Json<CoinDBCheck> {
@@ -150,6 +188,73 @@ async fn account(
     }
 }
 
+async fn address_history(
+    Path((address, offset, limit)): Path<(String, Option<u32>, Option<u32>)>,
+    State(node): State<Arc<Node>>,
+) -> Response<String> {
+    let Some(address_index) = node.coin_db().address_index() else {
+        return respond_error("Address index is not enabled");
+    };
+    let address_codec = node.wallet_db().address_codec();
+    let public_key = match address_codec.decode(&address) {
+        Ok(public_key) => public_key,
+        Err(err) => return respond_error(format!("Invalid address: {err}")),
+    };
+    let offset = offset.unwrap_or(0);
+    let limit = limit.unwrap_or(MAX_PAGE_SIZE).min(MAX_PAGE_SIZE);
+    let infos = address_index
+        .history(public_key, offset as usize, limit as usize)
+        .into_iter()
+        .map(|(key, hash)| AddressTxInfo::new(key, hash))
+        .collect::<Vec<_>>();
+    respond_json(&infos)
+}
+
+async fn transaction(Path(hash): Path<Hash>, State(node): State<Arc<Node>>) -> Response<String> {
+    let address_codec = node.wallet_db().address_codec();
+    let coin_db = node.coin_db();
+    if let Some(location) = coin_db.tx_index().and_then(|tx_index| tx_index.get(hash)) {
+        let block_db = node.block_db();
+        let (Some((block, _)), Some(index)) = (
+            block_db.get(location.block()),
+            block_db.index(location.block()),
+        ) else {
+            return respond_error("Block not found");
+        };
+        let Some(bytes) = block.raw_transactions().get(location.position() as usize) else {
+            return respond_error("Transaction not found");
+        };
+        match transaction_info(bytes, hash, address_codec) {
+            Ok(info) => respond_json(&TxLocationInfo::confirmed(
+                info,
+                location.block(),
+                index.height(),
+                coin_db.state().height(),
+            )),
+            Err(err) => respond_error(format!("Internal error: {err}")),
+        }
+    } else {
+        let tx_pool = node.tx_pool().read().unwrap();
+        if let Some(bytes) = tx_pool.get_raw(hash) {
+            match transaction_info(bytes, hash, address_codec) {
+                Ok(info) => respond_json(&TxLocationInfo::unconfirmed(info)),
+                Err(err) => respond_error(format!("Internal error: {err}")),
+            }
+        } else {
+            respond_error("Transaction not found")
+        }
+    }
+}
+
+fn transaction_info(
+    bytes: &[u8],
+    hash: Hash,
+    address_codec: &AddressCodec,
+) -> Result<TransactionInfo> {
+    let tx = from_bytes::<Transaction>(bytes, false)?;
+    TransactionInfo::new(&tx, hash, bytes.len(), address_codec)
+}
+
 pub fn routes() -> Router<Arc<Node>> {
     Router::new()
         .route("/api/v2/peerdb", get(peer_table))
@@ -158,9 +263,18 @@ pub fn routes() -> Router<Arc<Node>> {
         .route("/api/v2/block/{hash}/{txdetail}", get(block))
         .route("/api/v2/blockdb/check", get(block_db_check))
         .route("/api/v2/blockhash/{height}", get(block_hash))
+        .route("/api/v2/blocks/{height}/{count}", get(blocks))
         .route("/api/v2/blockindex/{hash}", get(block_index))
-        .route("/api/v2/makebootstrap", get(make_bootstrap))
         .route("/api/v2/ledger", get(coin_db))
         .route("/api/v2/ledger/check", get(coin_db_check))
         .route("/api/v2/account/{address}/{confirmations}", get(account))
+        .route(
+            "/api/v2/addresshistory/{address}/{offset}/{limit}",
+            get(address_history),
+        )
+        .route("/api/v2/transaction/{hash}", get(transaction))
+}
+
+pub fn admin_routes() -> Router<Arc<Node>> {
+    Router::new().route("/api/v2/makebootstrap", get(make_bootstrap))
 }
@@ -18,7 +18,9 @@
 #![allow(non_snake_case)]
 
 mod accountinfo;
+mod addresstxinfo;
 mod amountinfo;
+mod baninfo;
 mod bigintegerinfo;
 mod blockindexinfo;
 mod blockinfo;
@@ -29,6 +31,7 @@ pub mod database;
 mod endpointinfo;
 mod error;
 mod hashinfo;
+mod newaccountinfo;
 pub mod node;
 mod nodeinfo;
 mod peerinfo;
@@ -43,12 +46,19 @@ mod stakinginfo;
 mod transactioninfo;
 mod transactionnotification;
 pub mod txdatainfo;
+mod txlocationinfo;
 mod txpoolinfo;
+pub mod wallet;
+mod walletbalanceinfo;
+mod wallethistoryinfo;
+mod walletnotification;
 pub mod websocket;
 mod websocketnotification;
 
 pub use accountinfo::*;
+pub use addresstxinfo::*;
 pub use amountinfo::*;
+pub use baninfo::*;
 pub use bigintegerinfo::*;
 pub use blockindexinfo::*;
 pub use blockinfo::*;
@@ -58,15 +68,20 @@ pub use coindbinfo::*;
 pub use endpointinfo::*;
 pub use error::*;
 pub use hashinfo::*;
+pub use newaccountinfo::*;
 pub use nodeinfo::*;
 pub use peerinfo::*;
 pub use peertableinfo::*;
 pub use publickeyinfo::*;
-pub use routes::routes;
+pub use routes::{admin_routes, routes};
 pub use signatureinfo::*;
 pub use stakinginfo::*;
 pub use transactioninfo::*;
 pub use transactionnotification::*;
 pub use txdatainfo::TxDataInfo;
+pub use txlocationinfo::*;
 pub use txpoolinfo::*;
+pub use walletbalanceinfo::*;
+pub use wallethistoryinfo::*;
+pub use walletnotification::*;
 pub use websocketnotification::*;
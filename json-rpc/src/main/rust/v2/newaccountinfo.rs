@@ -0,0 +1,45 @@
+/*
+ * Copyright (c) 2026 Pavel Vasin
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Lesser General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::v2::Result;
+use blacknet_kernel::ed25519::{SecretKey, to_public_key};
+use blacknet_wallet::address::AddressCodec;
+use data_encoding::HEXUPPER;
+use serde::{Deserialize, Serialize};
+use zeroize::ZeroizeOnDrop;
+
+#[derive(Deserialize, Serialize, ZeroizeOnDrop)]
+pub struct NewAccountInfo {
+    mnemonic: String,
+    address: String,
+    publicKey: String,
+}
+
+impl NewAccountInfo {
+    pub fn new(
+        mnemonic: String,
+        secret_key: SecretKey,
+        address_codec: &AddressCodec,
+    ) -> Result<Self> {
+        let public_key = to_public_key(secret_key);
+        Ok(Self {
+            mnemonic,
+            address: address_codec.encode(public_key)?,
+            publicKey: HEXUPPER.encode(public_key.as_ref()),
+        })
+    }
+}
@@ -16,16 +16,19 @@
  */
 
 use crate::v2::response::*;
-use crate::v2::{NodeInfo, PeerInfo, TransactionInfo, TxPoolInfo, fork_cache_new};
+use crate::v2::{BanInfo, NodeInfo, PeerInfo, TransactionInfo, TxPoolInfo, fork_cache_new};
+use crate::v3::node::{
+    AddPeerRequest, AddPeerResponse, BanRequest, DisconnectPeerRequest, UnbanRequest,
+    add_peer_impl, ban_impl, disconnect_peer_impl, unban_impl,
+};
 use axum::{
-    Json, Router,
+    Form, Json, Router,
     extract::{Path, State},
     response::Response,
-    routing::get,
+    routing::{get, post},
 };
 use blacknet_kernel::blake2b::Hash;
 use blacknet_kernel::transaction::Transaction;
-use blacknet_network::endpoint::Endpoint;
 use blacknet_network::node::Node;
 use blacknet_serialization::format::from_bytes;
 use std::sync::Arc;
@@ -73,43 +76,33 @@ async fn tx_pool_transaction(
 }
 
 async fn add_peer(
-    Path((address, port, _force)): Path<(String, Option<u16>, Option<bool>)>,
+    Path((address, port, force)): Path<(String, Option<u16>, Option<bool>)>,
     State(node): State<Arc<Node>>,
 ) -> Response<String> {
-    let _endpoint = if let Some(endpoint) = Endpoint::parse(
-        &address,
-        port.unwrap_or_else(|| node.mode().default_p2p_port()),
-    ) {
-        endpoint
-    } else {
-        return respond_error("Invalid endpoint");
+    let request = AddPeerRequest {
+        address,
+        port,
+        force,
     };
-
-    todo!();
+    match add_peer_impl(&node, &request).await {
+        Ok(AddPeerResponse { id: Some(id) }) => respond_text(id.to_string()),
+        Ok(AddPeerResponse { id: None }) => respond_text("true"),
+        Err(err) => respond_error(err.message()),
+    }
 }
 
 async fn disconnect_peer_by_address(
     Path((address, port, _force)): Path<(String, Option<u16>, Option<bool>)>,
     State(node): State<Arc<Node>>,
 ) -> Response<String> {
-    let endpoint = if let Some(endpoint) = Endpoint::parse(
-        &address,
-        port.unwrap_or_else(|| node.mode().default_p2p_port()),
-    ) {
-        endpoint
-    } else {
-        return respond_error("Invalid endpoint");
+    let request = DisconnectPeerRequest {
+        id: None,
+        address: Some(address),
+        port,
     };
-
-    let connections = node.connections().read().unwrap();
-    if let Some(connection) = connections
-        .iter()
-        .find(|connection| connection.remote_endpoint() == endpoint)
-    {
-        connection.close();
-        respond_text("true")
-    } else {
-        respond_text("false")
+    match disconnect_peer_impl(&node, &request) {
+        Ok(response) => respond_bool(response.disconnected),
+        Err(err) => respond_error(err.message()),
     }
 }
 
@@ -117,12 +110,35 @@ async fn disconnect_peer(
     Path((id, _force)): Path<(u64, Option<bool>)>,
     State(node): State<Arc<Node>>,
 ) -> Response<String> {
-    let connections = node.connections().read().unwrap();
-    if let Some(connection) = connections.iter().find(|connection| connection.id() == id) {
-        connection.close();
-        respond_text("true")
-    } else {
-        respond_text("false")
+    let request = DisconnectPeerRequest {
+        id: Some(id),
+        address: None,
+        port: None,
+    };
+    match disconnect_peer_impl(&node, &request) {
+        Ok(response) => respond_bool(response.disconnected),
+        Err(err) => respond_error(err.message()),
+    }
+}
+
+async fn ban_list(State(node): State<Arc<Node>>) -> Json<Vec<BanInfo>> {
+    Json(node.ban_list().map(BanInfo::new))
+}
+
+async fn ban(State(node): State<Arc<Node>>, Form(request): Form<BanRequest>) -> Response<String> {
+    match ban_impl(&node, &request) {
+        Ok(()) => respond_bool(true),
+        Err(err) => respond_error(err.message()),
+    }
+}
+
+async fn unban(
+    State(node): State<Arc<Node>>,
+    Form(request): Form<UnbanRequest>,
+) -> Response<String> {
+    match unban_impl(&node, &request) {
+        Ok(response) => respond_bool(response.removed),
+        Err(err) => respond_error(err.message()),
     }
 }
 
@@ -135,10 +151,17 @@ pub fn routes() -> Router<Arc<Node>> {
             "/api/v2/txpool/transaction/{hash}/{raw}",
             get(tx_pool_transaction),
         )
+        .route("/api/v2/banlist", get(ban_list))
+}
+
+pub fn admin_routes() -> Router<Arc<Node>> {
+    Router::new()
         .route("/api/v2/addpeer/{address}/{port}/{force}", get(add_peer))
         .route(
             "/api/v2/disconnectpeerbyaddress/{address}/{port}/{force}",
             get(disconnect_peer_by_address),
         )
         .route("/api/v2/disconnectpeer/{id}/{force}", get(disconnect_peer))
+        .route("/api/v2/ban", post(ban))
+        .route("/api/v2/unban", post(unban))
 }
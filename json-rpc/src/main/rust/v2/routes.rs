@@ -20,11 +20,22 @@ use axum::Router;
 use blacknet_network::node::Node;
 use std::sync::Arc;
 
+/**
+ * Queries that don't change state of node
+ */
 pub fn routes() -> Router<Arc<Node>> {
     Router::new()
         .merge(v2::database::routes())
         .merge(v2::node::routes())
-        .merge(v2::sendtransaction::routes())
         .merge(v2::staking::routes())
+        .merge(v2::wallet::routes())
         .merge(v2::websocket::routes())
 }
+
+pub fn admin_routes() -> Router<Arc<Node>> {
+    Router::new()
+        .merge(v2::database::admin_routes())
+        .merge(v2::node::admin_routes())
+        .merge(v2::sendtransaction::admin_routes())
+        .merge(v2::staking::admin_routes())
+}
@@ -25,7 +25,7 @@ use axum::{
 };
 use blacknet_kernel::amount::Amount;
 use blacknet_kernel::blake2b::Hash;
-use blacknet_kernel::ed25519::{to_public_key, to_secret_key};
+use blacknet_kernel::ed25519::{SecretKey, to_public_key, to_secret_key};
 use blacknet_kernel::hashlock::HashLock;
 use blacknet_kernel::timelock::TimeLock;
 use blacknet_kernel::transaction::*;
@@ -35,11 +35,38 @@ use blacknet_wallet::address::AddressKind;
 use data_encoding::HEXUPPER_PERMISSIVE as HEX;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use zeroize::Zeroize;
+use zeroize::{Zeroize, Zeroizing};
+
+/**
+ * Legacy `mnemonic` takes precedence over the key of unlocked wallet `from`
+ */
+pub(crate) fn signer(
+    node: &Node,
+    mnemonic: Option<&str>,
+    from: Option<&str>,
+) -> Result<Zeroizing<SecretKey>, String> {
+    if let Some(mnemonic) = mnemonic {
+        to_secret_key(mnemonic)
+            .map(Zeroizing::new)
+            .ok_or_else(|| "Invalid mnemonic".to_owned())
+    } else if let Some(from) = from {
+        let wallet_db = node.wallet_db();
+        let public_key = wallet_db
+            .address_codec()
+            .decode(from)
+            .map_err(|err| format!("Invalid from: {err}"))?;
+        wallet_db
+            .secret_key(public_key)
+            .map_err(|err| err.to_string())
+    } else {
+        Err("Either mnemonic or from is required".to_owned())
+    }
+}
 
 #[derive(Deserialize, Serialize)]
 pub struct BundleRequest {
-    pub mnemonic: String,
+    pub mnemonic: Option<String>,
+    pub from: Option<String>,
     pub fee: Amount,
     pub id: String,
     pub data: String,
@@ -48,7 +75,9 @@ pub struct BundleRequest {
 
 impl Drop for BundleRequest {
     fn drop(&mut self) {
-        self.mnemonic.zeroize()
+        if let Some(mnemonic) = &mut self.mnemonic {
+            mnemonic.zeroize()
+        }
     }
 }
 
@@ -62,23 +91,17 @@ async fn bundle(
             return respond_error(format!("Invalid hex: {err}"));
         }
     };
-    let secret_key = if let Some(secret_key) = to_secret_key(&request.mnemonic) {
-        secret_key
-    } else {
-        return respond_error("Invalid mnemonic");
+    let secret_key = match signer(&node, request.mnemonic.as_deref(), request.from.as_deref()) {
+        Ok(secret_key) => secret_key,
+        Err(err) => return respond_error(err),
     };
     let anchor = if let Some(anchor) = request.referenceChain {
         anchor
     } else {
         node.wallet_db().anchor()
     };
-    let from = to_public_key(secret_key);
-    let seq = match node.wallet_db().sequence(from) {
-        Ok(seq) => seq,
-        Err(err) => {
-            return respond_error(err.to_string());
-        }
-    };
+    let from = to_public_key(*secret_key);
+    let seq = node.tx_pool().read().unwrap().sequence(from);
     let tag = match node
         .wallet_db()
         .address_codec()
@@ -100,7 +123,7 @@ async fn bundle(
         }
     };
     let mut tx = Transaction::new(from, seq, anchor, request.fee, TxKind::Blob, data.into());
-    let (hash, bytes) = tx.sign(secret_key);
+    let (hash, bytes) = tx.sign(*secret_key);
 
     match node.broadcast_tx(hash, &bytes) {
         Ok(()) => respond_text(hash.to_string()),
@@ -110,7 +133,8 @@ async fn bundle(
 
 #[derive(Deserialize, Serialize)]
 pub struct BurnRequest {
-    pub mnemonic: String,
+    pub mnemonic: Option<String>,
+    pub from: Option<String>,
     pub fee: Amount,
     pub amount: Amount,
     pub message: String,
@@ -119,7 +143,9 @@ pub struct BurnRequest {
 
 impl Drop for BurnRequest {
     fn drop(&mut self) {
-        self.mnemonic.zeroize()
+        if let Some(mnemonic) = &mut self.mnemonic {
+            mnemonic.zeroize()
+        }
     }
 }
 
@@ -130,23 +156,17 @@ async fn burn(State(node): State<Arc<Node>>, Form(request): Form<BurnRequest>) -
             return respond_error(format!("Invalid hex: {err}"));
         }
     };
-    let secret_key = if let Some(secret_key) = to_secret_key(&request.mnemonic) {
-        secret_key
-    } else {
-        return respond_error("Invalid mnemonic");
+    let secret_key = match signer(&node, request.mnemonic.as_deref(), request.from.as_deref()) {
+        Ok(secret_key) => secret_key,
+        Err(err) => return respond_error(err),
     };
     let anchor = if let Some(anchor) = request.referenceChain {
         anchor
     } else {
         node.wallet_db().anchor()
     };
-    let from = to_public_key(secret_key);
-    let seq = match node.wallet_db().sequence(from) {
-        Ok(seq) => seq,
-        Err(err) => {
-            return respond_error(err.to_string());
-        }
-    };
+    let from = to_public_key(*secret_key);
+    let seq = node.tx_pool().read().unwrap().sequence(from);
     let data = match to_bytes(&Burn::new(request.amount, message.into())) {
         Ok(data) => data,
         Err(err) => {
@@ -154,7 +174,7 @@ async fn burn(State(node): State<Arc<Node>>, Form(request): Form<BurnRequest>) -
         }
     };
     let mut tx = Transaction::new(from, seq, anchor, request.fee, TxKind::Burn, data.into());
-    let (hash, bytes) = tx.sign(secret_key);
+    let (hash, bytes) = tx.sign(*secret_key);
 
     match node.broadcast_tx(hash, &bytes) {
         Ok(()) => respond_text(hash.to_string()),
@@ -164,7 +184,8 @@ async fn burn(State(node): State<Arc<Node>>, Form(request): Form<BurnRequest>) -
 
 #[derive(Deserialize, Serialize)]
 pub struct CancelLeaseRequest {
-    pub mnemonic: String,
+    pub mnemonic: Option<String>,
+    pub from: Option<String>,
     pub fee: Amount,
     pub amount: Amount,
     pub to: String,
@@ -174,7 +195,9 @@ pub struct CancelLeaseRequest {
 
 impl Drop for CancelLeaseRequest {
     fn drop(&mut self) {
-        self.mnemonic.zeroize()
+        if let Some(mnemonic) = &mut self.mnemonic {
+            mnemonic.zeroize()
+        }
     }
 }
 
@@ -182,23 +205,17 @@ async fn cancel_lease(
     State(node): State<Arc<Node>>,
     Form(request): Form<CancelLeaseRequest>,
 ) -> Response<String> {
-    let secret_key = if let Some(secret_key) = to_secret_key(&request.mnemonic) {
-        secret_key
-    } else {
-        return respond_error("Invalid mnemonic");
+    let secret_key = match signer(&node, request.mnemonic.as_deref(), request.from.as_deref()) {
+        Ok(secret_key) => secret_key,
+        Err(err) => return respond_error(err),
     };
     let anchor = if let Some(anchor) = request.referenceChain {
         anchor
     } else {
         node.wallet_db().anchor()
     };
-    let from = to_public_key(secret_key);
-    let seq = match node.wallet_db().sequence(from) {
-        Ok(seq) => seq,
-        Err(err) => {
-            return respond_error(err.to_string());
-        }
-    };
+    let from = to_public_key(*secret_key);
+    let seq = node.tx_pool().read().unwrap().sequence(from);
     let to = match node.wallet_db().address_codec().decode(&request.to) {
         Ok(to) => to,
         Err(err) => {
@@ -219,7 +236,7 @@ async fn cancel_lease(
         TxKind::CancelLease,
         data.into(),
     );
-    let (hash, bytes) = tx.sign(secret_key);
+    let (hash, bytes) = tx.sign(*secret_key);
 
     match node.broadcast_tx(hash, &bytes) {
         Ok(()) => respond_text(hash.to_string()),
@@ -229,7 +246,8 @@ async fn cancel_lease(
 
 #[derive(Deserialize, Serialize)]
 pub struct ClaimSwapRequest {
-    pub mnemonic: String,
+    pub mnemonic: Option<String>,
+    pub from: Option<String>,
     pub fee: Amount,
     pub id: String,
     pub preimage: String,
@@ -238,7 +256,9 @@ pub struct ClaimSwapRequest {
 
 impl Drop for ClaimSwapRequest {
     fn drop(&mut self) {
-        self.mnemonic.zeroize()
+        if let Some(mnemonic) = &mut self.mnemonic {
+            mnemonic.zeroize()
+        }
     }
 }
 
@@ -252,23 +272,17 @@ async fn claim_swap(
             return respond_error(format!("Invalid hex: {err}"));
         }
     };
-    let secret_key = if let Some(secret_key) = to_secret_key(&request.mnemonic) {
-        secret_key
-    } else {
-        return respond_error("Invalid mnemonic");
+    let secret_key = match signer(&node, request.mnemonic.as_deref(), request.from.as_deref()) {
+        Ok(secret_key) => secret_key,
+        Err(err) => return respond_error(err),
     };
     let anchor = if let Some(anchor) = request.referenceChain {
         anchor
     } else {
         node.wallet_db().anchor()
     };
-    let from = to_public_key(secret_key);
-    let seq = match node.wallet_db().sequence(from) {
-        Ok(seq) => seq,
-        Err(err) => {
-            return respond_error(err.to_string());
-        }
-    };
+    let from = to_public_key(*secret_key);
+    let seq = node.tx_pool().read().unwrap().sequence(from);
     let id = match node
         .wallet_db()
         .address_codec()
@@ -297,7 +311,7 @@ async fn claim_swap(
         TxKind::ClaimHTLC,
         data.into(),
     );
-    let (hash, bytes) = tx.sign(secret_key);
+    let (hash, bytes) = tx.sign(*secret_key);
 
     match node.broadcast_tx(hash, &bytes) {
         Ok(()) => respond_text(hash.to_string()),
@@ -307,7 +321,8 @@ async fn claim_swap(
 
 #[derive(Deserialize, Serialize)]
 pub struct CreateSwapRequest {
-    pub mnemonic: String,
+    pub mnemonic: Option<String>,
+    pub from: Option<String>,
     pub fee: Amount,
     pub amount: Amount,
     pub to: String,
@@ -320,7 +335,9 @@ pub struct CreateSwapRequest {
 
 impl Drop for CreateSwapRequest {
     fn drop(&mut self) {
-        self.mnemonic.zeroize()
+        if let Some(mnemonic) = &mut self.mnemonic {
+            mnemonic.zeroize()
+        }
     }
 }
 
@@ -334,23 +351,17 @@ async fn create_swap(
             return respond_error(format!("Invalid hex: {err}"));
         }
     };
-    let secret_key = if let Some(secret_key) = to_secret_key(&request.mnemonic) {
-        secret_key
-    } else {
-        return respond_error("Invalid mnemonic");
+    let secret_key = match signer(&node, request.mnemonic.as_deref(), request.from.as_deref()) {
+        Ok(secret_key) => secret_key,
+        Err(err) => return respond_error(err),
     };
     let anchor = if let Some(anchor) = request.referenceChain {
         anchor
     } else {
         node.wallet_db().anchor()
     };
-    let from = to_public_key(secret_key);
-    let seq = match node.wallet_db().sequence(from) {
-        Ok(seq) => seq,
-        Err(err) => {
-            return respond_error(err.to_string());
-        }
-    };
+    let from = to_public_key(*secret_key);
+    let seq = node.tx_pool().read().unwrap().sequence(from);
     let to = match node.wallet_db().address_codec().decode(&request.to) {
         Ok(to) => to,
         Err(err) => {
@@ -373,7 +384,7 @@ async fn create_swap(
         TxKind::CreateHTLC,
         data.into(),
     );
-    let (hash, bytes) = tx.sign(secret_key);
+    let (hash, bytes) = tx.sign(*secret_key);
 
     match node.broadcast_tx(hash, &bytes) {
         Ok(()) => respond_text(hash.to_string()),
@@ -383,7 +394,8 @@ async fn create_swap(
 
 #[derive(Deserialize, Serialize)]
 pub struct LeaseRequest {
-    pub mnemonic: String,
+    pub mnemonic: Option<String>,
+    pub from: Option<String>,
     pub fee: Amount,
     pub amount: Amount,
     pub to: String,
@@ -392,7 +404,9 @@ pub struct LeaseRequest {
 
 impl Drop for LeaseRequest {
     fn drop(&mut self) {
-        self.mnemonic.zeroize()
+        if let Some(mnemonic) = &mut self.mnemonic {
+            mnemonic.zeroize()
+        }
     }
 }
 
@@ -400,23 +414,17 @@ async fn lease(
     State(node): State<Arc<Node>>,
     Form(request): Form<LeaseRequest>,
 ) -> Response<String> {
-    let secret_key = if let Some(secret_key) = to_secret_key(&request.mnemonic) {
-        secret_key
-    } else {
-        return respond_error("Invalid mnemonic");
+    let secret_key = match signer(&node, request.mnemonic.as_deref(), request.from.as_deref()) {
+        Ok(secret_key) => secret_key,
+        Err(err) => return respond_error(err),
     };
     let anchor = if let Some(anchor) = request.referenceChain {
         anchor
     } else {
         node.wallet_db().anchor()
     };
-    let from = to_public_key(secret_key);
-    let seq = match node.wallet_db().sequence(from) {
-        Ok(seq) => seq,
-        Err(err) => {
-            return respond_error(err.to_string());
-        }
-    };
+    let from = to_public_key(*secret_key);
+    let seq = node.tx_pool().read().unwrap().sequence(from);
     let to = match node.wallet_db().address_codec().decode(&request.to) {
         Ok(to) => to,
         Err(err) => {
@@ -430,7 +438,7 @@ async fn lease(
         }
     };
     let mut tx = Transaction::new(from, seq, anchor, request.fee, TxKind::Lease, data.into());
-    let (hash, bytes) = tx.sign(secret_key);
+    let (hash, bytes) = tx.sign(*secret_key);
 
     match node.broadcast_tx(hash, &bytes) {
         Ok(()) => respond_text(hash.to_string()),
@@ -440,7 +448,8 @@ async fn lease(
 
 #[derive(Deserialize, Serialize)]
 pub struct RefundSwapRequest {
-    pub mnemonic: String,
+    pub mnemonic: Option<String>,
+    pub from: Option<String>,
     pub fee: Amount,
     pub id: String,
     pub referenceChain: Option<Hash>,
@@ -448,7 +457,9 @@ pub struct RefundSwapRequest {
 
 impl Drop for RefundSwapRequest {
     fn drop(&mut self) {
-        self.mnemonic.zeroize()
+        if let Some(mnemonic) = &mut self.mnemonic {
+            mnemonic.zeroize()
+        }
     }
 }
 
@@ -456,23 +467,17 @@ async fn refund_swap(
     State(node): State<Arc<Node>>,
     Form(request): Form<RefundSwapRequest>,
 ) -> Response<String> {
-    let secret_key = if let Some(secret_key) = to_secret_key(&request.mnemonic) {
-        secret_key
-    } else {
-        return respond_error("Invalid mnemonic");
+    let secret_key = match signer(&node, request.mnemonic.as_deref(), request.from.as_deref()) {
+        Ok(secret_key) => secret_key,
+        Err(err) => return respond_error(err),
     };
     let anchor = if let Some(anchor) = request.referenceChain {
         anchor
     } else {
         node.wallet_db().anchor()
     };
-    let from = to_public_key(secret_key);
-    let seq = match node.wallet_db().sequence(from) {
-        Ok(seq) => seq,
-        Err(err) => {
-            return respond_error(err.to_string());
-        }
-    };
+    let from = to_public_key(*secret_key);
+    let seq = node.tx_pool().read().unwrap().sequence(from);
     let id = match node
         .wallet_db()
         .address_codec()
@@ -501,7 +506,7 @@ async fn refund_swap(
         TxKind::RefundHTLC,
         data.into(),
     );
-    let (hash, bytes) = tx.sign(secret_key);
+    let (hash, bytes) = tx.sign(*secret_key);
 
     match node.broadcast_tx(hash, &bytes) {
         Ok(()) => respond_text(hash.to_string()),
@@ -511,7 +516,8 @@ async fn refund_swap(
 
 #[derive(Deserialize, Serialize)]
 pub struct TransferRequest {
-    pub mnemonic: String,
+    pub mnemonic: Option<String>,
+    pub from: Option<String>,
     pub fee: Amount,
     pub amount: Amount,
     pub to: String,
@@ -522,7 +528,9 @@ pub struct TransferRequest {
 
 impl Drop for TransferRequest {
     fn drop(&mut self) {
-        self.mnemonic.zeroize()
+        if let Some(mnemonic) = &mut self.mnemonic {
+            mnemonic.zeroize()
+        }
     }
 }
 
@@ -530,23 +538,17 @@ async fn transfer(
     State(node): State<Arc<Node>>,
     Form(mut request): Form<TransferRequest>,
 ) -> Response<String> {
-    let secret_key = if let Some(secret_key) = to_secret_key(&request.mnemonic) {
-        secret_key
-    } else {
-        return respond_error("Invalid mnemonic");
+    let secret_key = match signer(&node, request.mnemonic.as_deref(), request.from.as_deref()) {
+        Ok(secret_key) => secret_key,
+        Err(err) => return respond_error(err),
     };
     let anchor = if let Some(anchor) = request.referenceChain {
         anchor
     } else {
         node.wallet_db().anchor()
     };
-    let from = to_public_key(secret_key);
-    let seq = match node.wallet_db().sequence(from) {
-        Ok(seq) => seq,
-        Err(err) => {
-            return respond_error(err.to_string());
-        }
-    };
+    let from = to_public_key(*secret_key);
+    let seq = node.tx_pool().read().unwrap().sequence(from);
     let to = match node.wallet_db().address_codec().decode(&request.to) {
         Ok(to) => to,
         Err(err) => {
@@ -574,7 +576,7 @@ async fn transfer(
         TxKind::Transfer,
         data.into(),
     );
-    let (hash, bytes) = tx.sign(secret_key);
+    let (hash, bytes) = tx.sign(*secret_key);
 
     match node.broadcast_tx(hash, &bytes) {
         Ok(()) => respond_text(hash.to_string()),
@@ -584,7 +586,8 @@ async fn transfer(
 
 #[derive(Deserialize, Serialize)]
 pub struct WithdrawFromLeaseRequest {
-    pub mnemonic: String,
+    pub mnemonic: Option<String>,
+    pub from: Option<String>,
     pub fee: Amount,
     pub withdraw: Amount,
     pub amount: Amount,
@@ -595,7 +598,9 @@ pub struct WithdrawFromLeaseRequest {
 
 impl Drop for WithdrawFromLeaseRequest {
     fn drop(&mut self) {
-        self.mnemonic.zeroize()
+        if let Some(mnemonic) = &mut self.mnemonic {
+            mnemonic.zeroize()
+        }
     }
 }
 
@@ -603,23 +608,17 @@ async fn withdraw_from_lease(
     State(node): State<Arc<Node>>,
     Form(request): Form<WithdrawFromLeaseRequest>,
 ) -> Response<String> {
-    let secret_key = if let Some(secret_key) = to_secret_key(&request.mnemonic) {
-        secret_key
-    } else {
-        return respond_error("Invalid mnemonic");
+    let secret_key = match signer(&node, request.mnemonic.as_deref(), request.from.as_deref()) {
+        Ok(secret_key) => secret_key,
+        Err(err) => return respond_error(err),
     };
     let anchor = if let Some(anchor) = request.referenceChain {
         anchor
     } else {
         node.wallet_db().anchor()
     };
-    let from = to_public_key(secret_key);
-    let seq = match node.wallet_db().sequence(from) {
-        Ok(seq) => seq,
-        Err(err) => {
-            return respond_error(err.to_string());
-        }
-    };
+    let from = to_public_key(*secret_key);
+    let seq = node.tx_pool().read().unwrap().sequence(from);
     let to = match node.wallet_db().address_codec().decode(&request.to) {
         Ok(to) => to,
         Err(err) => {
@@ -645,7 +644,7 @@ async fn withdraw_from_lease(
         TxKind::WithdrawFromLease,
         data.into(),
     );
-    let (hash, bytes) = tx.sign(secret_key);
+    let (hash, bytes) = tx.sign(*secret_key);
 
     match node.broadcast_tx(hash, &bytes) {
         Ok(()) => respond_text(hash.to_string()),
@@ -675,7 +674,7 @@ async fn send_raw_transaction(
     }
 }
 
-pub fn routes() -> Router<Arc<Node>> {
+pub fn admin_routes() -> Router<Arc<Node>> {
     Router::new()
         .route("/api/v2/bundle", post(bundle))
         .route("/api/v2/burn", post(burn))
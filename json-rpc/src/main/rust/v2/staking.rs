@@ -17,6 +17,7 @@
 
 use crate::v2::StakingInfo;
 use crate::v2::response::*;
+use crate::v2::sendtransaction::signer;
 use axum::{
     Form, Router,
     extract::{Path, State},
@@ -24,59 +25,83 @@ use axum::{
     routing::get,
     routing::post,
 };
-use blacknet_kernel::ed25519::{PublicKey, to_secret_key};
+use blacknet_kernel::ed25519::PublicKey;
 use blacknet_network::node::Node;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use zeroize::ZeroizeOnDrop;
+use zeroize::Zeroize;
 
-#[derive(Deserialize, Serialize, ZeroizeOnDrop)]
+#[derive(Deserialize, Serialize)]
 pub struct StartStakingRequest {
-    pub mnemonic: String,
+    pub mnemonic: Option<String>,
+    pub from: Option<String>,
+}
+
+impl Drop for StartStakingRequest {
+    fn drop(&mut self) {
+        if let Some(mnemonic) = &mut self.mnemonic {
+            mnemonic.zeroize()
+        }
+    }
 }
 
 async fn start_staking(
     State(node): State<Arc<Node>>,
     Form(request): Form<StartStakingRequest>,
 ) -> Response<String> {
-    let secret_key = if let Some(secret_key) = to_secret_key(&request.mnemonic) {
-        secret_key
-    } else {
-        return respond_error("Invalid mnemonic");
+    let secret_key = match signer(&node, request.mnemonic.as_deref(), request.from.as_deref()) {
+        Ok(secret_key) => secret_key,
+        Err(err) => return respond_error(err),
     };
     respond_bool(node.staker().start_staking(&secret_key))
 }
 
-#[derive(Deserialize, Serialize, ZeroizeOnDrop)]
+#[derive(Deserialize, Serialize)]
 pub struct StopStakingRequest {
-    pub mnemonic: String,
+    pub mnemonic: Option<String>,
+    pub from: Option<String>,
+}
+
+impl Drop for StopStakingRequest {
+    fn drop(&mut self) {
+        if let Some(mnemonic) = &mut self.mnemonic {
+            mnemonic.zeroize()
+        }
+    }
 }
 
 async fn stop_staking(
     State(node): State<Arc<Node>>,
     Form(request): Form<StopStakingRequest>,
 ) -> Response<String> {
-    let secret_key = if let Some(secret_key) = to_secret_key(&request.mnemonic) {
-        secret_key
-    } else {
-        return respond_error("Invalid mnemonic");
+    let secret_key = match signer(&node, request.mnemonic.as_deref(), request.from.as_deref()) {
+        Ok(secret_key) => secret_key,
+        Err(err) => return respond_error(err),
     };
     respond_bool(node.staker().stop_staking(&secret_key))
 }
 
-#[derive(Deserialize, Serialize, ZeroizeOnDrop)]
+#[derive(Deserialize, Serialize)]
 pub struct IsStakingRequest {
-    pub mnemonic: String,
+    pub mnemonic: Option<String>,
+    pub from: Option<String>,
+}
+
+impl Drop for IsStakingRequest {
+    fn drop(&mut self) {
+        if let Some(mnemonic) = &mut self.mnemonic {
+            mnemonic.zeroize()
+        }
+    }
 }
 
 async fn is_staking(
     State(node): State<Arc<Node>>,
     Form(request): Form<IsStakingRequest>,
 ) -> Response<String> {
-    let secret_key = if let Some(secret_key) = to_secret_key(&request.mnemonic) {
-        secret_key
-    } else {
-        return respond_error("Invalid mnemonic");
+    let secret_key = match signer(&node, request.mnemonic.as_deref(), request.from.as_deref()) {
+        Ok(secret_key) => secret_key,
+        Err(err) => return respond_error(err),
     };
     respond_bool(node.staker().is_staking(&secret_key))
 }
@@ -101,9 +126,15 @@ async fn staking(
 }
 
 pub fn routes() -> Router<Arc<Node>> {
+    Router::new().route("/api/v2/staking/{address}", get(staking))
+}
+
+/**
+ * These take mnemonic or unlocked wallet
+ */
+pub fn admin_routes() -> Router<Arc<Node>> {
     Router::new()
         .route("/api/v2/startstaking", post(start_staking))
         .route("/api/v2/stopstaking", post(stop_staking))
         .route("/api/v2/isstaking", post(is_staking))
-        .route("/api/v2/staking/{address}", get(staking))
 }
@@ -0,0 +1,49 @@
+/*
+ * Copyright (c) 2026 Pavel Vasin
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Lesser General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::v2::{HashInfo, TransactionInfo};
+use blacknet_kernel::blake2b::Hash;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+pub struct TxLocationInfo {
+    transaction: TransactionInfo,
+    blockHash: Option<HashInfo>,
+    height: Option<u32>,
+    confirmations: u32,
+}
+
+impl TxLocationInfo {
+    pub fn confirmed(transaction: TransactionInfo, block: Hash, height: u32, tip: u32) -> Self {
+        Self {
+            transaction,
+            blockHash: Some(block.into()),
+            height: Some(height),
+            // block may have been disconnected meanwhile
+            confirmations: (tip + 1).saturating_sub(height),
+        }
+    }
+
+    pub const fn unconfirmed(transaction: TransactionInfo) -> Self {
+        Self {
+            transaction,
+            blockHash: None,
+            height: None,
+            confirmations: 0,
+        }
+    }
+}
@@ -0,0 +1,99 @@
+/*
+ * Copyright (c) 2026 Pavel Vasin
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Lesser General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::v2::response::*;
+use crate::v2::{NewAccountInfo, WalletBalanceInfo, WalletHistoryInfo};
+use axum::{
+    Router,
+    extract::{Path, State},
+    response::Response,
+    routing::get,
+};
+use blacknet_compat::getentropy;
+use blacknet_kernel::ed25519::PublicKey;
+use blacknet_kernel::mnemonic::{generate, validate};
+use blacknet_network::node::Node;
+use std::sync::Arc;
+
+const MAX_PAGE_SIZE: u32 = 100;
+
+async fn generate_account(State(node): State<Arc<Node>>) -> Response<String> {
+    let mnemonic = match generate(getentropy) {
+        Ok(mnemonic) => mnemonic,
+        Err(err) => return respond_error(format!("Can't get entropy: {err}")),
+    };
+    let secret_key = match validate(&mnemonic) {
+        Ok(secret_key) => secret_key,
+        Err(err) => return respond_error(format!("Internal error: {err}")),
+    };
+    let address_codec = node.wallet_db().address_codec();
+    match NewAccountInfo::new(mnemonic, secret_key, address_codec) {
+        Ok(info) => respond_json(&info),
+        Err(err) => respond_error(format!("Internal error: {err}")),
+    }
+}
+
+async fn balance(Path(address): Path<String>, State(node): State<Arc<Node>>) -> Response<String> {
+    let public_key = match decode(&node, &address) {
+        Ok(public_key) => public_key,
+        Err(err) => return respond_error(err),
+    };
+    match node.wallet_balance(public_key) {
+        Ok((balance, unconfirmed_balance)) => {
+            respond_json(&WalletBalanceInfo::new(balance, unconfirmed_balance))
+        }
+        Err(err) => respond_error(err.to_string()),
+    }
+}
+
+async fn history(
+    Path((address, offset, limit)): Path<(String, Option<u32>, Option<u32>)>,
+    State(node): State<Arc<Node>>,
+) -> Response<String> {
+    let public_key = match decode(&node, &address) {
+        Ok(public_key) => public_key,
+        Err(err) => return respond_error(err),
+    };
+    let offset = offset.unwrap_or(0);
+    let limit = limit.unwrap_or(MAX_PAGE_SIZE).min(MAX_PAGE_SIZE);
+    match node.wallet_db().history(public_key, offset, limit) {
+        Ok(entries) => respond_json(
+            &entries
+                .into_iter()
+                .map(WalletHistoryInfo::from)
+                .collect::<Vec<_>>(),
+        ),
+        Err(err) => respond_error(err.to_string()),
+    }
+}
+
+fn decode(node: &Node, address: &str) -> Result<PublicKey, String> {
+    node.wallet_db()
+        .address_codec()
+        .decode(address)
+        .map_err(|err| format!("Invalid address: {err}"))
+}
+
+pub fn routes() -> Router<Arc<Node>> {
+    Router::new()
+        .route("/api/v2/generateaccount", get(generate_account))
+        .route("/api/v2/wallet/{address}/balance", get(balance))
+        .route(
+            "/api/v2/wallet/{address}/history/{offset}/{limit}",
+            get(history),
+        )
+}
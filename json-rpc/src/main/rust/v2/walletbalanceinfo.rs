@@ -0,0 +1,35 @@
+/*
+ * Copyright (c) 2026 Pavel Vasin
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Lesser General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::v2::AmountInfo;
+use blacknet_kernel::amount::Amount;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+pub struct WalletBalanceInfo {
+    balance: AmountInfo,
+    unconfirmedBalance: AmountInfo,
+}
+
+impl WalletBalanceInfo {
+    pub fn new(balance: Amount, unconfirmed_balance: Amount) -> Self {
+        Self {
+            balance: balance.into(),
+            unconfirmedBalance: unconfirmed_balance.into(),
+        }
+    }
+}
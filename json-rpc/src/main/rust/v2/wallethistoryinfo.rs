@@ -0,0 +1,42 @@
+/*
+ * Copyright (c) 2026 Pavel Vasin
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Lesser General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::v2::HashInfo;
+use blacknet_wallet::wallet::HistoryEntry;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+pub struct WalletHistoryInfo {
+    hash: HashInfo,
+    kind: u8,
+    height: Option<u32>,
+    time: i64,
+    // signed
+    delta: String,
+}
+
+impl From<HistoryEntry> for WalletHistoryInfo {
+    fn from(entry: HistoryEntry) -> Self {
+        Self {
+            hash: entry.hash().into(),
+            kind: entry.kind(),
+            height: entry.height(),
+            time: entry.time().value(),
+            delta: entry.delta().to_string(),
+        }
+    }
+}
@@ -15,6 +15,9 @@
  * along with this program. If not, see <https://www.gnu.org/licenses/>.
  */
 
+use crate::v2::{
+    BlockNotification, Result, TransactionNotification, WalletNotification, WebSocketNotification,
+};
 use axum::{
     Router,
     extract::{
@@ -24,9 +27,70 @@ use axum::{
     response::Response,
     routing::any,
 };
+use blacknet_kernel::blake2b::Hash;
+use blacknet_kernel::block::Block;
+use blacknet_kernel::ed25519::PublicKey;
+use blacknet_kernel::transaction::Transaction;
 use blacknet_network::node::Node;
+use blacknet_network::notifier::Notification;
+use blacknet_serialization::format::from_bytes;
+use blacknet_time::Seconds;
+use futures_util::sink::SinkExt;
 use futures_util::stream::{SplitSink, SplitStream, StreamExt};
-use std::sync::Arc;
+use serde::Deserialize;
+use serde_json::{from_str, to_string};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Mutex as AsyncMutex;
+use tokio::sync::broadcast::error::RecvError;
+
+/**
+ * `{"command":"subscribe","route":"block"}`, `"transaction"` for TxPool,
+ * or `"wallet"` with `"address"` for transactions involving it.
+ * A request that can't be served is answered on `"error"` route.
+ */
+#[derive(Deserialize)]
+struct Request {
+    command: String,
+    route: String,
+    address: Option<String>,
+}
+
+#[derive(Default)]
+struct Subscriptions {
+    blocks: bool,
+    transactions: bool,
+    wallets: HashSet<PublicKey>,
+}
+
+impl Subscriptions {
+    fn update(&mut self, request: Request, node: &Node) -> Result<()> {
+        let subscribe = match request.command.as_str() {
+            "subscribe" => true,
+            "unsubscribe" => false,
+            command => return Err(format!("Unknown command {command}").into()),
+        };
+        match request.route.as_str() {
+            "block" => self.blocks = subscribe,
+            "transaction" => self.transactions = subscribe,
+            "wallet" => {
+                let address = request.address.ok_or("Missing address")?;
+                let public_key = node.wallet_db().address_codec().decode(&address)?;
+                if subscribe {
+                    self.wallets.insert(public_key);
+                } else {
+                    self.wallets.remove(&public_key);
+                }
+            }
+            route => return Err(format!("Unknown route {route}").into()),
+        }
+        Ok(())
+    }
+
+    fn is_empty(&self) -> bool {
+        !self.blocks && !self.transactions && self.wallets.is_empty()
+    }
+}
 
 async fn upgrade(ws: WebSocketUpgrade, State(node): State<Arc<Node>>) -> Response {
     ws.on_upgrade(|socket| handle(socket, node))
@@ -34,21 +98,187 @@ async fn upgrade(ws: WebSocketUpgrade, State(node): State<Arc<Node>>) -> Respons
 
 async fn handle(socket: WebSocket, node: Arc<Node>) {
     let (sink, stream) = socket.split();
-    tokio::spawn(sender(sink));
-    tokio::spawn(receiver(stream, node));
+    let sink = Arc::new(AsyncMutex::new(sink));
+    let subscriptions = Arc::new(Mutex::new(Subscriptions::default()));
+    let sender = tokio::spawn(sender(sink.clone(), node.clone(), subscriptions.clone()));
+    receiver(stream, sink, node, subscriptions).await;
+    sender.abort();
 }
 
-async fn receiver(mut stream: SplitStream<WebSocket>, _node: Arc<Node>) {
+async fn receiver(
+    mut stream: SplitStream<WebSocket>,
+    sink: Arc<AsyncMutex<SplitSink<WebSocket, Message>>>,
+    node: Arc<Node>,
+    subscriptions: Arc<Mutex<Subscriptions>>,
+) {
     while let Some(Ok(message)) = stream.next().await {
-        #[expect(clippy::match_single_binding)]
         match message {
-            _ => todo!(),
+            Message::Text(text) => {
+                let result = from_str::<Request>(text.as_str())
+                    .map_err(Into::into)
+                    .and_then(|request| subscriptions.lock().unwrap().update(request, &node));
+                // subscriptions stay as they were
+                if let Err(err) = result
+                    && let Ok(message) =
+                        to_string(&WebSocketNotification::with_error(err.to_string()))
+                    && sink
+                        .lock()
+                        .await
+                        .send(Message::Text(message.into()))
+                        .await
+                        .is_err()
+                {
+                    break;
+                }
+            }
+            Message::Close(_) => break,
+            _ => (),
+        }
+    }
+}
+
+async fn sender(
+    sink: Arc<AsyncMutex<SplitSink<WebSocket, Message>>>,
+    node: Arc<Node>,
+    subscriptions: Arc<Mutex<Subscriptions>>,
+) {
+    let mut notifications = node.notifier().subscribe();
+    loop {
+        let notification = match notifications.recv().await {
+            Ok(notification) => notification,
+            // lagging client misses some notifications
+            Err(RecvError::Lagged(_)) => continue,
+            Err(RecvError::Closed) => break,
+        };
+        let messages = match notify(&node, &subscriptions.lock().unwrap(), notification) {
+            Ok(messages) => messages,
+            Err(_) => continue,
+        };
+        let mut sink = sink.lock().await;
+        for message in messages {
+            if sink.send(Message::Text(message.into())).await.is_err() {
+                return;
+            }
         }
     }
+    let _ = sink.lock().await.close().await;
 }
 
-async fn sender(_sink: SplitSink<WebSocket, Message>) {
-    //TODO
+fn notify(
+    node: &Node,
+    subscriptions: &Subscriptions,
+    notification: Notification,
+) -> Result<Vec<String>> {
+    let mut messages = Vec::new();
+    if subscriptions.is_empty() {
+        return Ok(messages);
+    }
+    let address_codec = node.wallet_db().address_codec();
+    match notification {
+        Notification::Block {
+            hash,
+            height,
+            bytes,
+        } => {
+            if !subscriptions.blocks && subscriptions.wallets.is_empty() {
+                return Ok(messages);
+            }
+            let block = from_bytes::<Block>(&bytes, false)?;
+            if subscriptions.blocks {
+                let notification = BlockNotification::new(
+                    &block,
+                    hash,
+                    height,
+                    bytes.len() as u32,
+                    address_codec,
+                )?;
+                messages.push(to_string(&WebSocketNotification::with_block(
+                    notification,
+                )?)?);
+            }
+            for bytes in block.raw_transactions() {
+                if subscriptions.wallets.is_empty() {
+                    break;
+                }
+                let Some(tx_hash) = Transaction::compute_hash(bytes) else {
+                    continue;
+                };
+                let tx = from_bytes::<Transaction>(bytes, false)?;
+                notify_wallets(
+                    node,
+                    subscriptions,
+                    &tx,
+                    tx_hash,
+                    block.time(),
+                    bytes.len() as u32,
+                    height,
+                    &mut messages,
+                )?;
+            }
+        }
+        Notification::Transaction { hash, time, bytes } => {
+            if !subscriptions.transactions && subscriptions.wallets.is_empty() {
+                return Ok(messages);
+            }
+            let tx = from_bytes::<Transaction>(&bytes, false)?;
+            let time = Seconds::new(time.value() / 1000);
+            if subscriptions.transactions {
+                let notification = TransactionNotification::new(
+                    &tx,
+                    hash,
+                    time,
+                    bytes.len() as u32,
+                    address_codec,
+                )?;
+                messages.push(to_string(&WebSocketNotification::with_transaction(
+                    notification,
+                )?)?);
+            }
+            notify_wallets(
+                node,
+                subscriptions,
+                &tx,
+                hash,
+                time,
+                bytes.len() as u32,
+                0,
+                &mut messages,
+            )?;
+        }
+    }
+    Ok(messages)
+}
+
+fn notify_wallets(
+    node: &Node,
+    subscriptions: &Subscriptions,
+    tx: &Transaction,
+    hash: Hash,
+    time: Seconds,
+    size: u32,
+    height: u32,
+    messages: &mut Vec<String>,
+) -> Result<()> {
+    let address_codec = node.wallet_db().address_codec();
+    let mut involved = vec![tx.from()];
+    for (_, public_key) in tx.recipients()? {
+        if !involved.contains(&public_key) {
+            involved.push(public_key);
+        }
+    }
+    for public_key in involved {
+        if subscriptions.wallets.contains(&public_key) {
+            let notification = WalletNotification::new(
+                address_codec.encode(public_key)?,
+                height,
+                TransactionNotification::new(tx, hash, time, size, address_codec)?,
+            );
+            messages.push(to_string(&WebSocketNotification::with_wallet(
+                notification,
+            )?)?);
+        }
+    }
+    Ok(())
 }
 
 pub fn routes() -> Router<Arc<Node>> {
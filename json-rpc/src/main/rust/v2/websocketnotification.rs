@@ -15,7 +15,7 @@
  * along with this program. If not, see <https://www.gnu.org/licenses/>.
  */
 
-use crate::v2::{BlockNotification, Result, TransactionNotification};
+use crate::v2::{BlockNotification, Result, TransactionNotification, WalletNotification};
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, to_value};
 
@@ -39,4 +39,18 @@ impl WebSocketNotification {
             message: to_value(notification)?,
         })
     }
+
+    pub fn with_wallet(notification: WalletNotification) -> Result<Self> {
+        Ok(Self {
+            route: "wallet".to_owned(),
+            message: to_value(notification)?,
+        })
+    }
+
+    pub fn with_error(message: String) -> Self {
+        Self {
+            route: "error".to_owned(),
+            message: Value::String(message),
+        }
+    }
 }
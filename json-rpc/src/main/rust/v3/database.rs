@@ -0,0 +1,55 @@
+/*
+ * Copyright (c) 2026 Pavel Vasin
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Lesser General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::v2::ErrorInfo;
+use axum::{Json, Router, extract::State, http::StatusCode, routing::post};
+use blacknet_network::node::Node;
+use serde::{Deserialize, Serialize};
+use std::path::absolute;
+use std::sync::Arc;
+
+#[derive(Deserialize, Serialize)]
+pub struct MakeBootstrapResponse {
+    pub path: String,
+}
+
+pub(crate) fn make_bootstrap_impl(node: &Node) -> Result<MakeBootstrapResponse, ErrorInfo> {
+    let block_db = node.block_db();
+    let coin_db = node.coin_db();
+    match block_db.export(&coin_db.state()) {
+        Some(path) => {
+            let path = absolute(&path).unwrap_or(path);
+            Ok(MakeBootstrapResponse {
+                path: path.display().to_string(),
+            })
+        }
+        None => Err(ErrorInfo::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Not synchronized",
+        )),
+    }
+}
+
+async fn make_bootstrap(
+    State(node): State<Arc<Node>>,
+) -> Result<Json<MakeBootstrapResponse>, ErrorInfo> {
+    make_bootstrap_impl(&node).map(Json)
+}
+
+pub fn admin_routes() -> Router<Arc<Node>> {
+    Router::new().route("/api/v3/makebootstrap", post(make_bootstrap))
+}
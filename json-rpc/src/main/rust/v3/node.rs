@@ -0,0 +1,198 @@
+/*
+ * Copyright (c) 2026 Pavel Vasin
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Lesser General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::v2::ErrorInfo;
+use axum::{
+    Json, Router,
+    extract::{State, rejection::JsonRejection},
+    http::StatusCode,
+    routing::post,
+};
+use blacknet_network::banlist::{DEFAULT_BAN_TIME, MAX_BAN_TIME};
+use blacknet_network::endpoint::Endpoint;
+use blacknet_network::node::Node;
+use blacknet_time::Milliseconds;
+use core::iter::once;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Deserialize, Serialize)]
+pub struct AddPeerRequest {
+    pub address: String,
+    pub port: Option<u16>,
+    pub force: Option<bool>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct AddPeerResponse {
+    // none if queued
+    pub id: Option<u64>,
+}
+
+pub(crate) async fn add_peer_impl(
+    node: &Arc<Node>,
+    request: &AddPeerRequest,
+) -> Result<AddPeerResponse, ErrorInfo> {
+    let endpoint = parse_endpoint(node, &request.address, request.port)?;
+    if request.force.unwrap_or(false) {
+        match node.add_peer(endpoint).await {
+            Ok(connection) => Ok(AddPeerResponse {
+                id: Some(connection.id()),
+            }),
+            Err(err) => Err(ErrorInfo::bad_request(format!("Can't connect: {err}"))),
+        }
+    } else if node.peer_table().add(once(endpoint)) != 0 {
+        Ok(AddPeerResponse { id: None })
+    } else {
+        Err(ErrorInfo::bad_request(
+            "Not added: endpoint is local, private, obsolete, already known or peer table is full",
+        ))
+    }
+}
+
+async fn add_peer(
+    State(node): State<Arc<Node>>,
+    request: Result<Json<AddPeerRequest>, JsonRejection>,
+) -> Result<Json<AddPeerResponse>, ErrorInfo> {
+    let Json(request) = request?;
+    add_peer_impl(&node, &request).await.map(Json)
+}
+
+/**
+ * Either id or address
+ */
+#[derive(Deserialize, Serialize)]
+pub struct DisconnectPeerRequest {
+    pub id: Option<u64>,
+    pub address: Option<String>,
+    pub port: Option<u16>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct DisconnectPeerResponse {
+    pub disconnected: bool,
+}
+
+pub(crate) fn disconnect_peer_impl(
+    node: &Node,
+    request: &DisconnectPeerRequest,
+) -> Result<DisconnectPeerResponse, ErrorInfo> {
+    let endpoint = match (request.id, &request.address) {
+        (Some(_), None) => None,
+        (None, Some(address)) => Some(parse_endpoint(node, address, request.port)?),
+        _ => return Err(ErrorInfo::bad_request("Expected either id or address")),
+    };
+    let connections = node.connections().read().unwrap();
+    let connection = connections.iter().find(|connection| match endpoint {
+        Some(endpoint) => connection.remote_endpoint() == endpoint,
+        None => Some(connection.id()) == request.id,
+    });
+    if let Some(connection) = connection {
+        connection.close();
+    }
+    Ok(DisconnectPeerResponse {
+        disconnected: connection.is_some(),
+    })
+}
+
+async fn disconnect_peer(
+    State(node): State<Arc<Node>>,
+    request: Result<Json<DisconnectPeerRequest>, JsonRejection>,
+) -> Result<Json<DisconnectPeerResponse>, ErrorInfo> {
+    let Json(request) = request?;
+    disconnect_peer_impl(&node, &request).map(Json)
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct BanRequest {
+    pub address: String,
+    pub subnet: Option<bool>,
+    pub seconds: Option<i64>,
+}
+
+pub(crate) fn ban_impl(node: &Node, request: &BanRequest) -> Result<(), ErrorInfo> {
+    let endpoint = parse_endpoint(node, &request.address, None)?;
+    let duration = match request.seconds {
+        Some(seconds) if seconds > 0 && seconds <= MAX_BAN_TIME.value() / 1000 => {
+            Milliseconds::with_seconds(seconds)
+        }
+        Some(_) => return Err(ErrorInfo::bad_request("Invalid duration")),
+        None => DEFAULT_BAN_TIME,
+    };
+    if node.ban(
+        endpoint,
+        request.subnet.unwrap_or(false),
+        duration,
+        "Manually banned".to_owned(),
+    ) {
+        Ok(())
+    } else {
+        Err(ErrorInfo::bad_request("Subnet is not known"))
+    }
+}
+
+async fn ban(
+    State(node): State<Arc<Node>>,
+    request: Result<Json<BanRequest>, JsonRejection>,
+) -> Result<StatusCode, ErrorInfo> {
+    let Json(request) = request?;
+    ban_impl(&node, &request).map(|()| StatusCode::NO_CONTENT)
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct UnbanRequest {
+    pub address: String,
+    pub subnet: Option<bool>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct UnbanResponse {
+    pub removed: bool,
+}
+
+pub(crate) fn unban_impl(node: &Node, request: &UnbanRequest) -> Result<UnbanResponse, ErrorInfo> {
+    let endpoint = parse_endpoint(node, &request.address, None)?;
+    Ok(UnbanResponse {
+        removed: node
+            .ban_list()
+            .unban(endpoint, request.subnet.unwrap_or(false)),
+    })
+}
+
+async fn unban(
+    State(node): State<Arc<Node>>,
+    request: Result<Json<UnbanRequest>, JsonRejection>,
+) -> Result<Json<UnbanResponse>, ErrorInfo> {
+    let Json(request) = request?;
+    unban_impl(&node, &request).map(Json)
+}
+
+fn parse_endpoint(node: &Node, address: &str, port: Option<u16>) -> Result<Endpoint, ErrorInfo> {
+    Endpoint::parse(
+        address,
+        port.unwrap_or_else(|| node.mode().default_p2p_port()),
+    )
+    .ok_or_else(|| ErrorInfo::bad_request("Invalid endpoint"))
+}
+
+pub fn admin_routes() -> Router<Arc<Node>> {
+    Router::new()
+        .route("/api/v3/addpeer", post(add_peer))
+        .route("/api/v3/disconnectpeer", post(disconnect_peer))
+        .route("/api/v3/ban", post(ban))
+        .route("/api/v3/unban", post(unban))
+}
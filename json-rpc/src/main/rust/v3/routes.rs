@@ -0,0 +1,31 @@
+/*
+ * Copyright (c) 2026 Pavel Vasin
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Lesser General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::v3;
+use axum::Router;
+use blacknet_network::node::Node;
+use std::sync::Arc;
+
+/**
+ * State changing requests take POST with JSON body, and failures respond with `ErrorInfo`
+ */
+pub fn admin_routes() -> Router<Arc<Node>> {
+    Router::new()
+        .merge(v3::database::admin_routes())
+        .merge(v3::node::admin_routes())
+        .merge(v3::wallet::admin_routes())
+}
@@ -0,0 +1,293 @@
+/*
+ * Copyright (c) 2026 Pavel Vasin
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Lesser General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::v2::ErrorInfo;
+use axum::{
+    Json, Router,
+    extract::{State, rejection::JsonRejection},
+    http::StatusCode,
+    routing::post,
+};
+use blacknet_kernel::ed25519::{PublicKey, to_public_key, to_secret_key};
+use blacknet_kernel::mnemonic::validate;
+use blacknet_network::node::Node;
+use blacknet_time::Seconds;
+use blacknet_wallet::wallet::MAX_UNLOCK_TIMEOUT;
+use blacknet_wallet::walletdb::Error as WalletError;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::task::spawn_blocking;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+const DEFAULT_UNLOCK_TIMEOUT: Seconds = Seconds::with_minutes(5);
+
+/**
+ * Stores secret key encrypted with password, so that later requests can sign by address
+ */
+#[derive(Deserialize, Serialize, ZeroizeOnDrop)]
+pub struct EncryptWalletRequest {
+    pub mnemonic: String,
+    pub password: String,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct EncryptWalletResponse {
+    pub address: String,
+}
+
+async fn encrypt_wallet(
+    State(node): State<Arc<Node>>,
+    request: Result<Json<EncryptWalletRequest>, JsonRejection>,
+) -> Result<Json<EncryptWalletResponse>, ErrorInfo> {
+    let Json(request) = request?;
+    let secret_key = to_secret_key(&request.mnemonic)
+        .ok_or_else(|| ErrorInfo::bad_request("Invalid mnemonic"))?;
+    {
+        let node = node.clone();
+        blocking(move || {
+            node.wallet_db()
+                .set_secret_key(secret_key, &request.password)
+        })
+        .await?;
+    }
+    let address = node
+        .wallet_db()
+        .address_codec()
+        .encode(to_public_key(secret_key))
+        .map_err(|err| ErrorInfo::internal(err.to_string()))?;
+    Ok(Json(EncryptWalletResponse { address }))
+}
+
+/**
+ * Timeout in seconds
+ */
+#[derive(Deserialize, Serialize, ZeroizeOnDrop)]
+pub struct UnlockWalletRequest {
+    pub address: String,
+    pub password: String,
+    pub timeout: Option<i64>,
+}
+
+async fn unlock_wallet(
+    State(node): State<Arc<Node>>,
+    request: Result<Json<UnlockWalletRequest>, JsonRejection>,
+) -> Result<StatusCode, ErrorInfo> {
+    let Json(request) = request?;
+    let public_key = parse_address(&node, &request.address)?;
+    let timeout = match request.timeout {
+        Some(seconds) if seconds > 0 && seconds <= MAX_UNLOCK_TIMEOUT.value() => {
+            Seconds::new(seconds)
+        }
+        Some(_) => return Err(ErrorInfo::bad_request("Invalid timeout")),
+        None => DEFAULT_UNLOCK_TIMEOUT,
+    };
+    blocking(move || {
+        node.wallet_db()
+            .unlock(public_key, &request.password, timeout)
+    })
+    .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct LockWalletRequest {
+    pub address: String,
+}
+
+async fn lock_wallet(
+    State(node): State<Arc<Node>>,
+    request: Result<Json<LockWalletRequest>, JsonRejection>,
+) -> Result<StatusCode, ErrorInfo> {
+    let Json(request) = request?;
+    let public_key = parse_address(&node, &request.address)?;
+    node.wallet_db().lock(public_key)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/**
+ * Either address of watch-only wallet, or mnemonic with optional password to store the encrypted key
+ */
+#[derive(Deserialize, Serialize)]
+pub struct CreateWalletRequest {
+    pub address: Option<String>,
+    pub mnemonic: Option<String>,
+    pub password: Option<String>,
+}
+
+impl Drop for CreateWalletRequest {
+    fn drop(&mut self) {
+        if let Some(mnemonic) = &mut self.mnemonic {
+            mnemonic.zeroize()
+        }
+        if let Some(password) = &mut self.password {
+            password.zeroize()
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct CreateWalletResponse {
+    pub address: String,
+}
+
+async fn create_wallet(
+    State(node): State<Arc<Node>>,
+    request: Result<Json<CreateWalletRequest>, JsonRejection>,
+) -> Result<Json<CreateWalletResponse>, ErrorInfo> {
+    let Json(request) = request?;
+    let public_key = {
+        let node = node.clone();
+        blocking(move || create_wallet_impl(&node, &request)).await?
+    };
+    wallet_response(&node, public_key)
+}
+
+/**
+ * Like create, and then rescans blocks from birth height of the wallet
+ */
+#[derive(Deserialize, Serialize)]
+pub struct ImportWalletRequest {
+    #[serde(flatten)]
+    pub wallet: CreateWalletRequest,
+    pub height: Option<u32>,
+}
+
+async fn import_wallet(
+    State(node): State<Arc<Node>>,
+    request: Result<Json<ImportWalletRequest>, JsonRejection>,
+) -> Result<Json<CreateWalletResponse>, ErrorInfo> {
+    let Json(request) = request?;
+    let height = check_height(&node, request.height)?;
+    let public_key = {
+        let node = node.clone();
+        blocking(move || {
+            let public_key = create_wallet_impl(&node, &request.wallet)?;
+            node.rescan_wallet(public_key, height)?;
+            Ok::<_, ErrorInfo>(public_key)
+        })
+        .await?
+    };
+    wallet_response(&node, public_key)
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct RescanWalletRequest {
+    pub address: String,
+    pub height: Option<u32>,
+}
+
+async fn rescan_wallet(
+    State(node): State<Arc<Node>>,
+    request: Result<Json<RescanWalletRequest>, JsonRejection>,
+) -> Result<StatusCode, ErrorInfo> {
+    let Json(request) = request?;
+    let public_key = parse_address(&node, &request.address)?;
+    let height = check_height(&node, request.height)?;
+    blocking(move || node.rescan_wallet(public_key, height)).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn create_wallet_impl(node: &Node, request: &CreateWalletRequest) -> Result<PublicKey, ErrorInfo> {
+    let secret_key = match &request.mnemonic {
+        Some(mnemonic) => Some(
+            validate(mnemonic)
+                .map_err(|err| ErrorInfo::bad_request(format!("Invalid mnemonic: {err}")))?,
+        ),
+        None => None,
+    };
+    let public_key = match (&request.address, secret_key) {
+        (Some(address), None) => parse_address(node, address)?,
+        (None, Some(secret_key)) => to_public_key(secret_key),
+        _ => {
+            return Err(ErrorInfo::bad_request(
+                "Expected either address or mnemonic",
+            ));
+        }
+    };
+    if request.password.is_some() && secret_key.is_none() {
+        return Err(ErrorInfo::bad_request("Password requires mnemonic"));
+    }
+    node.create_wallet(public_key)?;
+    if let (Some(secret_key), Some(password)) = (secret_key, &request.password) {
+        node.wallet_db().set_secret_key(secret_key, password)?;
+    }
+    Ok(public_key)
+}
+
+fn check_height(node: &Node, height: Option<u32>) -> Result<u32, ErrorInfo> {
+    let height = height.unwrap_or(0);
+    if height > node.coin_db().state().height() {
+        return Err(ErrorInfo::bad_request("Height is ahead of the chain"));
+    }
+    Ok(height)
+}
+
+fn wallet_response(
+    node: &Node,
+    public_key: PublicKey,
+) -> Result<Json<CreateWalletResponse>, ErrorInfo> {
+    let address = node
+        .wallet_db()
+        .address_codec()
+        .encode(public_key)
+        .map_err(|err| ErrorInfo::internal(err.to_string()))?;
+    Ok(Json(CreateWalletResponse { address }))
+}
+
+fn parse_address(node: &Node, address: &str) -> Result<PublicKey, ErrorInfo> {
+    node.wallet_db()
+        .address_codec()
+        .decode(address)
+        .map_err(|err| ErrorInfo::bad_request(format!("Invalid address: {err}")))
+}
+
+impl From<WalletError> for ErrorInfo {
+    fn from(err: WalletError) -> Self {
+        match err {
+            WalletError::UnknownWallet => Self::not_found(err.to_string()),
+            WalletError::WalletExists => Self::new(StatusCode::CONFLICT, err.to_string()),
+            WalletError::Entropy(_) | WalletError::Kdf(_) | WalletError::Sqlite(_) => {
+                Self::internal(err.to_string())
+            }
+            _ => Self::bad_request(err.to_string()),
+        }
+    }
+}
+
+/**
+ * Password hashing and rescan take a while, so they run outside of the async workers
+ */
+async fn blocking<T, E>(f: impl FnOnce() -> Result<T, E> + Send + 'static) -> Result<T, ErrorInfo>
+where
+    T: Send + 'static,
+    E: Into<ErrorInfo> + Send + 'static,
+{
+    match spawn_blocking(f).await {
+        Ok(result) => result.map_err(Into::into),
+        Err(err) => Err(ErrorInfo::internal(err.to_string())),
+    }
+}
+
+pub fn admin_routes() -> Router<Arc<Node>> {
+    Router::new()
+        .route("/api/v3/wallet/encrypt", post(encrypt_wallet))
+        .route("/api/v3/wallet/unlock", post(unlock_wallet))
+        .route("/api/v3/wallet/lock", post(lock_wallet))
+        .route("/api/v3/wallet/create", post(create_wallet))
+        .route("/api/v3/wallet/import", post(import_wallet))
+        .route("/api/v3/wallet/rescan", post(rescan_wallet))
+}
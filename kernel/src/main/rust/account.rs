@@ -41,6 +41,15 @@ impl Account {
         }
     }
 
+    pub const fn with_stake(stake: Amount) -> Self {
+        Self {
+            seq: 0,
+            stake,
+            immature: Vec::new(),
+            leases: Vec::new(),
+        }
+    }
+
     pub const fn seq(&self) -> u32 {
         self.seq
     }
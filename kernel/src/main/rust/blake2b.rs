@@ -15,6 +15,7 @@
  * along with this program. If not, see <https://www.gnu.org/licenses/>.
  */
 
+use core::array::TryFromSliceError;
 use core::fmt::{Debug, Display, Formatter, Result as FmtResult};
 use data_encoding::{DecodeError, DecodeKind, HEXUPPER};
 use serde::{Deserialize, Serialize};
@@ -56,6 +57,14 @@ impl From<Hash> for [u8; 32] {
     }
 }
 
+impl TryFrom<&[u8]> for Hash {
+    type Error = TryFromSliceError;
+
+    fn try_from(slice: &[u8]) -> Result<Self, Self::Error> {
+        Ok(Self(slice.try_into()?))
+    }
+}
+
 impl TryFrom<&str> for Hash {
     type Error = DecodeError;
 
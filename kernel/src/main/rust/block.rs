@@ -16,10 +16,12 @@
  */
 
 use crate::blake2b::Hash;
-use crate::ed25519::{PublicKey, Signature, verify};
+use crate::ed25519::{PublicKey, SecretKey, Signature, sign, verify};
 use crate::error::{Error, Result};
 use alloc::boxed::Box;
+use alloc::vec::Vec;
 use blacknet_crypto::symmetric::Blake2b256;
+use blacknet_serialization::format::to_bytes;
 use blacknet_time::Seconds;
 use serde::{Deserialize, Serialize};
 
@@ -124,6 +126,17 @@ impl Block {
         }
     }
 
+    pub fn sign(&mut self, secret_key: SecretKey) -> (Hash, Vec<u8>) {
+        let mut bytes = to_bytes(&self).expect("Block serialization");
+        self.content_hash = Self::compute_content_hash(&bytes).expect("Block serialized");
+        bytes[CONTENT_HASH_POS..SIGNATURE_POS].copy_from_slice(self.content_hash.as_ref());
+        let hash = Self::compute_hash(&bytes).expect("Block serialized");
+        self.signature = sign(hash, secret_key);
+        bytes[SIGNATURE_POS..SIGNATURE_POS + 32].copy_from_slice(self.signature.raw_r());
+        bytes[SIGNATURE_POS + 32..HEADER_SIZE_BYTES].copy_from_slice(self.signature.raw_s());
+        (hash, bytes)
+    }
+
     pub fn verify_signature(&self, hash: Hash) -> Result<()> {
         verify(self.signature, hash, self.generator)
     }
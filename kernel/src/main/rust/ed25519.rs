@@ -173,6 +173,12 @@ impl AsRef<[u8]> for SecretKey {
     }
 }
 
+impl From<[u8; 32]> for SecretKey {
+    fn from(array: [u8; 32]) -> Self {
+        Self(array)
+    }
+}
+
 impl TryFrom<&str> for SecretKey {
     type Error = DecodeError;
 
@@ -192,7 +198,7 @@ impl TryFrom<&str> for SecretKey {
     }
 }
 
-const fn check_version(bytes: [u8; 32]) -> bool {
+pub(crate) const fn check_version(bytes: [u8; 32]) -> bool {
     bytes[0] & 0xF0 == 0x10
 }
 
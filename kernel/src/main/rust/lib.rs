@@ -27,6 +27,7 @@ pub mod ed25519;
 pub mod error;
 pub mod hashlock;
 pub mod htlc;
+pub mod mnemonic;
 pub mod multisig;
 pub mod proofofstake;
 pub mod timelock;
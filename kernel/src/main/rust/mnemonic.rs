@@ -0,0 +1,208 @@
+/*
+ * Copyright (c) 2026 Pavel Vasin
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Lesser General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::ed25519::{SecretKey, check_version};
+use alloc::string::String;
+use alloc::vec::Vec;
+use blacknet_crypto::symmetric::Blake2b256;
+use core::fmt;
+use zeroize::Zeroize;
+
+pub const WORD_COUNT: usize = 12;
+
+/**
+ * BIP-39 word lists bundled with the crate
+ */
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Language {
+    English,
+    ChineseSimplified,
+    ChineseTraditional,
+    Italian,
+    Korean,
+}
+
+impl Language {
+    pub const ALL: [Language; 5] = [
+        Language::English,
+        Language::ChineseSimplified,
+        Language::ChineseTraditional,
+        Language::Italian,
+        Language::Korean,
+    ];
+
+    pub fn words(self) -> Vec<&'static str> {
+        match self {
+            Language::English => include_str!("../resources/bip39/english.txt"),
+            Language::ChineseSimplified => {
+                include_str!("../resources/bip39/chinese_simplified.txt")
+            }
+            Language::ChineseTraditional => {
+                include_str!("../resources/bip39/chinese_traditional.txt")
+            }
+            Language::Italian => include_str!("../resources/bip39/italian.txt"),
+            Language::Korean => include_str!("../resources/bip39/korean.txt"),
+        }
+        .lines()
+        .collect()
+    }
+}
+
+/**
+ * BIP-39 English word list, that is 2048 sorted words with unique 4-letter prefixes
+ */
+pub fn words() -> Vec<&'static str> {
+    Language::English.words()
+}
+
+/**
+ * Samples words until the hash has the right version. Only the version nibble serves as a checksum,
+ * so about one of 16 random phrases is accepted.
+ */
+pub fn generate<E>(mut getentropy: impl FnMut(&mut [u8]) -> Result<(), E>) -> Result<String, E> {
+    let words = words();
+    let mut entropy = [0u8; WORD_COUNT * 2];
+    loop {
+        getentropy(&mut entropy)?;
+        let mut mnemonic = String::new();
+        for (i, pair) in entropy.chunks_exact(2).enumerate() {
+            if i != 0 {
+                mnemonic.push(' ');
+            }
+            let index = u16::from_le_bytes([pair[0], pair[1]]) as usize % words.len();
+            mnemonic.push_str(words[index]);
+        }
+        if check_version(Blake2b256::digest(&mnemonic)) {
+            entropy.zeroize();
+            return Ok(mnemonic);
+        }
+        mnemonic.zeroize();
+    }
+}
+
+/**
+ * Stricter than `to_secret_key`, which accepts any phrase of the right version.
+ * Reports the first problem found, so that a typo can be located.
+ * The language is the one that knows most leading words of the phrase.
+ */
+pub fn validate(mnemonic: &str) -> Result<SecretKey, MnemonicError> {
+    let phrase = mnemonic.split_whitespace().collect::<Vec<_>>();
+    // reversed, so that a tie goes to the former language
+    let words = Language::ALL
+        .iter()
+        .rev()
+        .map(|language| language.words())
+        .max_by_key(|words| known(words, &phrase))
+        .expect("at least one language");
+    let position = known(&words, &phrase);
+    if position < phrase.len() {
+        return Err(MnemonicError::UnknownWord {
+            position: position + 1,
+            suggestion: suggest(&words, phrase[position]),
+        });
+    }
+    if phrase.len() != WORD_COUNT {
+        return Err(MnemonicError::WordCount(phrase.len()));
+    }
+    if phrase.join(" ") != mnemonic {
+        return Err(MnemonicError::Whitespace);
+    }
+    let bytes: [u8; 32] = Blake2b256::digest(mnemonic);
+    if check_version(bytes) {
+        Ok(SecretKey::from(bytes))
+    } else {
+        Err(MnemonicError::Checksum)
+    }
+}
+
+// the number of leading words found in the list
+fn known(words: &[&str], phrase: &[&str]) -> usize {
+    phrase
+        .iter()
+        .position(|word| !words.contains(word))
+        .unwrap_or(phrase.len())
+}
+
+// a unique prefix or the closest word within two edits, that is not a wild guess for short words
+fn suggest(words: &[&'static str], word: &str) -> Option<&'static str> {
+    if let Some(prefix) = word.get(..4)
+        && let Some(candidate) = words.iter().find(|candidate| candidate.starts_with(prefix))
+    {
+        return Some(*candidate);
+    }
+    words
+        .iter()
+        .map(|candidate| (distance(candidate, word), *candidate))
+        .filter(|(distance, _)| *distance <= 2 && *distance < word.chars().count())
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+// Levenshtein
+fn distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum MnemonicError {
+    UnknownWord {
+        position: usize,
+        suggestion: Option<&'static str>,
+    },
+    WordCount(usize),
+    Whitespace,
+    Checksum,
+}
+
+impl fmt::Display for MnemonicError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MnemonicError::UnknownWord {
+                position,
+                suggestion,
+            } => {
+                write!(f, "Unknown word at position {position}")?;
+                if let Some(suggestion) = suggestion {
+                    write!(f, ", did you mean {suggestion}?")?;
+                }
+                Ok(())
+            }
+            MnemonicError::WordCount(count) => {
+                write!(f, "Expected {WORD_COUNT} words, got {count}")
+            }
+            MnemonicError::Whitespace => {
+                write!(f, "Words must be separated by single spaces")
+            }
+            MnemonicError::Checksum => {
+                write!(f, "Checksum mismatch, a word may be mistyped or misplaced")
+            }
+        }
+    }
+}
+
+impl core::error::Error for MnemonicError {}
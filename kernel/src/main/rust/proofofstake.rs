@@ -120,6 +120,30 @@ pub fn max_block_size(block_sizes: &VecDeque<u32>) -> u32 {
     size.clamp(DEFAULT_MAX_BLOCK_SIZE, MAX_BLOCK_SIZE)
 }
 
+/**
+ * Stake of network as estimated from difficulty, saturating at the maximum amount
+ */
+pub fn network_weight(version: Version, difficulty: UInt256) -> Amount {
+    let weight = MAX_DIFFICULTY / difficulty / target_block_time(version).value() as u64;
+    let weight: UInt320 = weight.widening_mul_limb(time_slot(version).value() as u64);
+    match weight.limbs() {
+        [weight, 0, 0, 0, 0] => Amount::new(weight),
+        _ => Amount::MAX,
+    }
+}
+
+/**
+ * Expected time to stake a block with `weight`, saturating at the maximum time
+ */
+pub fn expected_time(version: Version, weight: Amount, network_weight: Amount) -> Seconds {
+    if weight == Amount::ZERO {
+        return Seconds::ZERO;
+    }
+    let n = target_block_time(version).value() as u128 * network_weight.value() as u128
+        / weight.value() as u128;
+    Seconds::new(n.try_into().unwrap_or(i64::MAX))
+}
+
 /**
  * Length of time slot
  */
@@ -19,11 +19,13 @@ use crate::amount::Amount;
 use crate::blake2b::Hash;
 use crate::ed25519::{PublicKey, SecretKey, Signature, sign, verify};
 use crate::error::Result;
-use crate::transaction::TxKind;
+use crate::transaction::{
+    Batch, CancelLease, CreateHTLC, CreateMultisig, Lease, Transfer, TxKind, WithdrawFromLease,
+};
 use alloc::boxed::Box;
 use alloc::vec::Vec;
 use blacknet_crypto::symmetric::Blake2b256;
-use blacknet_serialization::format::to_bytes;
+use blacknet_serialization::format::{from_bytes, to_bytes};
 use serde::{Deserialize, Serialize};
 
 const HEADER_SIZE_BYTES: usize = size_of::<Signature>()
@@ -124,4 +126,63 @@ impl Transaction {
     pub const fn data_bytes(&self) -> &[u8] {
         &self.data
     }
+
+    /**
+     * Public keys named in the data, paired with data index as in `TxData::process_impl`.
+     * Parties of already existing contracts are not resolved.
+     */
+    pub fn recipients(&self) -> Result<Vec<(u32, PublicKey)>> {
+        let mut recipients = Vec::new();
+        recipients_impl(self.kind, &self.data, 0, &mut recipients)?;
+        Ok(recipients)
+    }
+}
+
+fn recipients_impl(
+    kind: TxKind,
+    data_bytes: &[u8],
+    data_index: u32,
+    recipients: &mut Vec<(u32, PublicKey)>,
+) -> Result<()> {
+    match kind {
+        TxKind::Transfer => {
+            let data = from_bytes::<Transfer>(data_bytes, false)?;
+            recipients.push((data_index, data.to()));
+        }
+        TxKind::Lease => {
+            let data = from_bytes::<Lease>(data_bytes, false)?;
+            recipients.push((data_index, data.to()));
+        }
+        TxKind::CancelLease => {
+            let data = from_bytes::<CancelLease>(data_bytes, false)?;
+            recipients.push((data_index, data.to()));
+        }
+        TxKind::WithdrawFromLease => {
+            let data = from_bytes::<WithdrawFromLease>(data_bytes, false)?;
+            recipients.push((data_index, data.to()));
+        }
+        TxKind::CreateHTLC => {
+            let data = from_bytes::<CreateHTLC>(data_bytes, false)?;
+            recipients.push((data_index, data.to()));
+        }
+        TxKind::CreateMultisig => {
+            let data = from_bytes::<CreateMultisig>(data_bytes, false)?;
+            for deposit in data.deposits() {
+                recipients.push((data_index, deposit.from()));
+            }
+        }
+        TxKind::Batch if data_index == 0 => {
+            let data = from_bytes::<Batch>(data_bytes, false)?;
+            for (index, batchee) in data.multi_data().iter().enumerate() {
+                recipients_impl(
+                    batchee.kind(),
+                    batchee.data_bytes(),
+                    (index + 1) as u32,
+                    recipients,
+                )?;
+            }
+        }
+        _ => (),
+    }
+    Ok(())
 }
@@ -17,7 +17,7 @@
 
 use blacknet_kernel::blake2b::Hash;
 use blacknet_kernel::block::Block;
-use blacknet_kernel::ed25519::{PublicKey, Signature};
+use blacknet_kernel::ed25519::{PublicKey, Signature, to_public_key, to_secret_key};
 use blacknet_serialization::format::{from_bytes, to_bytes};
 use data_encoding::HEXUPPER;
 
@@ -46,3 +46,14 @@ fn hash() {
     assert_eq!(Block::compute_hash(&invalid_bytes), None);
     assert_eq!(Block::compute_content_hash(&invalid_bytes), None);
 }
+
+#[test]
+fn signing() {
+    let secret_key = to_secret_key("疗 昨 示 穿 偏 贷 五 袁 色 烂 撒 殖").unwrap();
+    let mut block = Block::new(Hash::ZERO, 1545556624.into(), to_public_key(secret_key));
+    let (hash, bytes) = block.sign(secret_key);
+    assert_eq!(Block::compute_hash(&bytes), Some(hash));
+    assert_eq!(from_bytes::<Block>(&bytes, false).unwrap(), block);
+    assert!(block.verify_content_hash(&bytes).is_ok());
+    assert!(block.verify_signature(hash).is_ok());
+}
@@ -0,0 +1,112 @@
+/*
+ * Copyright (c) 2026 Pavel Vasin
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Lesser General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use blacknet_kernel::ed25519::{to_public_key, to_secret_key};
+use blacknet_kernel::mnemonic::*;
+use core::convert::Infallible;
+
+#[test]
+fn word_list() {
+    let words = words();
+    assert_eq!(words.len(), 2048);
+    assert!(words.is_sorted());
+}
+
+#[test]
+fn generation() {
+    let mut counter = 0u8;
+    let mnemonic = generate(|buf: &mut [u8]| {
+        for byte in buf {
+            *byte = counter;
+            counter = counter.wrapping_add(1);
+        }
+        Ok::<(), Infallible>(())
+    })
+    .unwrap();
+    assert_eq!(
+        mnemonic,
+        "chimney hair possible trip cinnamon harsh praise truth claw heart pride turn"
+    );
+    let secret_key = validate(&mnemonic).unwrap();
+    assert_eq!(
+        to_public_key(secret_key),
+        to_public_key(to_secret_key(&mnemonic).unwrap())
+    );
+}
+
+#[test]
+fn validation() {
+    assert!(matches!(
+        validate("chimney hair possible trip cinamon harsh praise truth claw heart pride turn"),
+        Err(MnemonicError::UnknownWord {
+            position: 5,
+            suggestion: Some("cinnamon"),
+            ..
+        })
+    ));
+    assert!(matches!(
+        validate("chimney hair possible trip cinnamon harsh praise truth claw heart pride"),
+        Err(MnemonicError::WordCount(11))
+    ));
+    assert!(matches!(
+        validate("chimney hair possible trip cinnamon harsh praise truth claw heart pride  turn"),
+        Err(MnemonicError::Whitespace)
+    ));
+    assert!(matches!(
+        validate("hair possible trip cinnamon harsh praise truth claw heart pride turn chimney"),
+        Err(MnemonicError::Checksum)
+    ));
+}
+
+#[test]
+fn languages() {
+    for language in Language::ALL {
+        let words = language.words();
+        assert_eq!(words.len(), 2048, "{language:?}");
+        let mut phrase = String::new();
+        for offset in 0.. {
+            phrase = (0..WORD_COUNT)
+                .map(|i| words[(offset * WORD_COUNT + i * 131) % words.len()])
+                .collect::<Vec<_>>()
+                .join(" ");
+            if to_secret_key(&phrase).is_some() {
+                break;
+            }
+        }
+        let secret_key = validate(&phrase).unwrap();
+        assert_eq!(
+            to_public_key(secret_key),
+            to_public_key(to_secret_key(&phrase).unwrap())
+        );
+    }
+    assert!(matches!(
+        validate(
+            "abaco abbaglio abbinato abetr abisso abolire abrasivo abrogato acca accento acciaio accordo"
+        ),
+        Err(MnemonicError::UnknownWord {
+            position: 4,
+            suggestion: Some("abete"),
+        })
+    ));
+    assert!(matches!(
+        validate("的 一 是 在 不 了 有 和 人 这 中 猫"),
+        Err(MnemonicError::UnknownWord {
+            position: 12,
+            suggestion: None,
+        })
+    ));
+}
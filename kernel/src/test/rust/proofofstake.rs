@@ -0,0 +1,62 @@
+/*
+ * Copyright (c) 2026 Pavel Vasin
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Lesser General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use blacknet_crypto::bigint::UInt256;
+use blacknet_kernel::amount::Amount;
+use blacknet_kernel::proofofstake::{MAX_DIFFICULTY, Version};
+use blacknet_time::Seconds;
+
+#[test]
+fn network_weight() {
+    let difficulty = UInt256::from([0, 0, 0, 1 << 32]);
+    assert_eq!(
+        blacknet_kernel::proofofstake::network_weight(Version::V4_1, difficulty),
+        Amount::new((u32::MAX as u64) / 16 * 4)
+    );
+    assert_eq!(
+        blacknet_kernel::proofofstake::network_weight(Version::V4_1, MAX_DIFFICULTY),
+        Amount::ZERO
+    );
+    assert_eq!(
+        blacknet_kernel::proofofstake::network_weight(Version::V4_1, UInt256::from(1)),
+        Amount::MAX
+    );
+}
+
+#[test]
+fn expected_time() {
+    assert_eq!(
+        blacknet_kernel::proofofstake::expected_time(
+            Version::V4_1,
+            Amount::new(10),
+            Amount::new(1000)
+        ),
+        Seconds::new(1600)
+    );
+    assert_eq!(
+        blacknet_kernel::proofofstake::expected_time(
+            Version::V4_1,
+            Amount::ZERO,
+            Amount::new(1000)
+        ),
+        Seconds::ZERO
+    );
+    assert_eq!(
+        blacknet_kernel::proofofstake::expected_time(Version::V4_1, Amount::new(1), Amount::MAX),
+        Seconds::new(i64::MAX)
+    );
+}
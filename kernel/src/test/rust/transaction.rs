@@ -15,10 +15,38 @@
  * along with this program. If not, see <https://www.gnu.org/licenses/>.
  */
 
-use blacknet_kernel::transaction::Transaction;
+use blacknet_kernel::amount::Amount;
+use blacknet_kernel::blake2b::Hash;
+use blacknet_kernel::ed25519::PublicKey;
+use blacknet_kernel::transaction::{PaymentId, Transaction, Transfer, TxKind};
+use blacknet_serialization::format::to_bytes;
 
 #[test]
 fn hash() {
     let invalid_bytes: [u8; 4] = [0, 1, 2, 3];
     assert_eq!(Transaction::compute_hash(&invalid_bytes), None);
 }
+
+#[test]
+fn recipients() {
+    let from =
+        PublicKey::try_from("27A2C7CE9EE9AF0458832079017A5FBBB1F1551932C4CB901396BAE95F7D0F0A")
+            .unwrap();
+    let to =
+        PublicKey::try_from("B7E64C1BC5ADD0593397E75E827A8DA323EA8C6E1FE6142A86092C9359117E50")
+            .unwrap();
+    let transfer = Transfer::new(Amount::new(1), to, PaymentId::plain(""));
+    let data = to_bytes(&transfer).unwrap().into_boxed_slice();
+    let tx = Transaction::new(
+        from,
+        0,
+        Hash::default(),
+        Amount::new(0),
+        TxKind::Transfer,
+        data,
+    );
+    assert_eq!(tx.recipients().unwrap(), [(0, to)]);
+
+    let tx = Transaction::generated(from, 1, Hash::default(), Amount::new(0));
+    assert!(tx.recipients().unwrap().is_empty());
+}
@@ -0,0 +1,147 @@
+/*
+ * Copyright (c) 2026 Pavel Vasin
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Lesser General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::dbview::DBView;
+use crate::fjall::Fjall;
+use crate::genesis;
+use blacknet_kernel::blake2b::Hash;
+use blacknet_kernel::block::Block;
+use blacknet_kernel::ed25519::PublicKey;
+use blacknet_kernel::transaction::Transaction;
+use blacknet_serialization::format::from_bytes;
+use fjall::{OwnedWriteBatch as WriteBatch, Result};
+
+const TIP_KEY: &str = "tip";
+const KEY_SIZE: usize = 32 + 4 + 4 + 4;
+
+/**
+ * Public key, height, position in block and data index, so that history of an address is a prefix
+ */
+#[derive(Clone, Copy)]
+pub struct Key([u8; KEY_SIZE]);
+
+impl Key {
+    pub fn new(public_key: PublicKey, height: u32, position: u32, data_index: u32) -> Self {
+        let mut key = [0; KEY_SIZE];
+        key[0..32].copy_from_slice(public_key.as_ref());
+        key[32..36].copy_from_slice(&height.to_be_bytes());
+        key[36..40].copy_from_slice(&position.to_be_bytes());
+        key[40..44].copy_from_slice(&data_index.to_be_bytes());
+        Self(key)
+    }
+
+    fn u32_at(&self, offset: usize) -> u32 {
+        u32::from_be_bytes(self.0[offset..offset + 4].try_into().unwrap())
+    }
+
+    pub fn height(&self) -> u32 {
+        self.u32_at(32)
+    }
+
+    pub fn position(&self) -> u32 {
+        self.u32_at(36)
+    }
+
+    /**
+     * Zero is the transaction as a whole, including its sender
+     */
+    pub fn data_index(&self) -> u32 {
+        self.u32_at(40)
+    }
+}
+
+impl AsRef<[u8]> for Key {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl TryFrom<&[u8]> for Key {
+    type Error = core::array::TryFromSliceError;
+
+    fn try_from(bytes: &[u8]) -> core::result::Result<Self, Self::Error> {
+        Ok(Self(bytes.try_into()?))
+    }
+}
+
+/**
+ * Optional index of transactions by public keys involved in them
+ */
+pub struct AddressIndex {
+    entries: DBView<Key, Hash>,
+    tips: DBView<&'static str, Hash>,
+}
+
+impl AddressIndex {
+    pub fn new(fjall: &Fjall) -> Result<Self> {
+        Ok(Self {
+            entries: DBView::new(fjall, "addresses")?,
+            tips: DBView::new(fjall, "addressindex")?,
+        })
+    }
+
+    /**
+     * Newest first
+     */
+    pub fn history(&self, public_key: PublicKey, offset: usize, limit: usize) -> Vec<(Key, Hash)> {
+        self.entries
+            .prefix(public_key.as_ref())
+            .rev()
+            .skip(offset)
+            .take(limit)
+            .collect()
+    }
+
+    pub fn is_synchronized(&self, tip: Hash) -> bool {
+        self.tips.get(TIP_KEY) == Some(tip)
+    }
+
+    pub fn connect(&self, batch: &mut WriteBatch, hash: Hash, height: u32, block: &Block) {
+        for (key, tx_hash) in Self::collect(height, block) {
+            self.entries.batch(batch, key, &tx_hash);
+        }
+        self.tips.batch(batch, TIP_KEY, &hash);
+    }
+
+    pub fn disconnect(&self, batch: &mut WriteBatch, height: u32, block: &Block) {
+        for (key, _) in Self::collect(height, block) {
+            self.entries.batch_remove(batch, key);
+        }
+        self.tips.batch(batch, TIP_KEY, &block.previous());
+    }
+
+    pub fn clear(&self, batch: &mut WriteBatch) {
+        for (key, _) in self.entries.iter() {
+            self.entries.batch_remove(batch, key);
+        }
+        self.tips.batch(batch, TIP_KEY, &genesis::hash());
+    }
+
+    fn collect(height: u32, block: &Block) -> Vec<(Key, Hash)> {
+        let mut entries = Vec::new();
+        for (position, bytes) in block.raw_transactions().iter().enumerate() {
+            let position = position as u32;
+            let tx = from_bytes::<Transaction>(bytes, false).expect("valid tx in connected block");
+            let hash = Transaction::compute_hash(bytes).expect("Hashable tx");
+            entries.push((Key::new(tx.from(), height, position, 0), hash));
+            for (data_index, public_key) in tx.recipients().expect("valid tx in connected block") {
+                entries.push((Key::new(public_key, height, position, data_index), hash));
+            }
+        }
+        entries
+    }
+}
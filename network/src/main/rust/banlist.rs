@@ -0,0 +1,232 @@
+/*
+ * Copyright (c) 2026 Pavel Vasin
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Lesser General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::endpoint::Endpoint;
+use crate::settings::Settings;
+use blacknet_compat::XDGDirectories;
+use blacknet_io::file::replace;
+use blacknet_log::{LogManager, Logger, error, info, warn};
+use blacknet_serialization::format::{from_read, to_write};
+use blacknet_time::{Milliseconds, SystemClock};
+use core::error::Error;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, ErrorKind, Read, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+
+pub const DEFAULT_BAN_TIME: Milliseconds = Milliseconds::with_days(1);
+pub const MAX_BAN_TIME: Milliseconds = Milliseconds::with_days(10 * 365);
+const FILE_VERSION: u32 = 1;
+const FILE_NAME: &str = "banlist.dat";
+
+pub struct BanList {
+    logger: Logger,
+    settings: Arc<Settings>,
+    state_dir: PathBuf,
+    bans: RwLock<Bans>,
+}
+
+impl BanList {
+    pub fn new(
+        dirs: &XDGDirectories,
+        log_manager: &LogManager,
+        settings: Arc<Settings>,
+    ) -> Result<Arc<Self>, Box<dyn Error>> {
+        let ban_list = Self {
+            logger: log_manager.logger("BanList")?,
+            settings,
+            state_dir: dirs.state().to_owned(),
+            bans: RwLock::new(Bans::default()),
+        };
+        match ban_list.load() {
+            Ok(()) => {
+                let len = ban_list.len();
+                if len != 0 {
+                    info!(ban_list.logger, "Loaded {len} bans");
+                }
+            }
+            Err(err) => {
+                warn!(ban_list.logger, "{err}");
+            }
+        }
+        Ok(Arc::new(ban_list))
+    }
+
+    pub fn len(&self) -> usize {
+        let bans = self.bans.read().unwrap();
+        bans.hosts.len() + bans.subnets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn is_banned(&self, endpoint: Endpoint) -> bool {
+        let now = SystemClock::millis();
+        let bans = self.bans.read().unwrap();
+        let banned = |entry: &Entry| entry.is_active(now);
+        bans.hosts.get(&endpoint.host()).is_some_and(banned)
+            || endpoint
+                .subnet()
+                .and_then(|subnet| bans.subnets.get(&subnet))
+                .is_some_and(banned)
+    }
+
+    /**
+     * Returns false if subnet of endpoint is unknown. Duration is capped at `MAX_BAN_TIME`.
+     */
+    pub fn ban(
+        &self,
+        endpoint: Endpoint,
+        subnet: bool,
+        duration: Milliseconds,
+        reason: String,
+    ) -> bool {
+        let now = SystemClock::millis();
+        let entry = Entry {
+            until: now + duration.min(MAX_BAN_TIME),
+            reason,
+        };
+        {
+            let mut bans = self.bans.write().unwrap();
+            if subnet {
+                let Some(subnet) = endpoint.subnet() else {
+                    return false;
+                };
+                bans.subnets.insert(subnet, entry);
+            } else {
+                bans.hosts.insert(endpoint.host(), entry);
+            }
+            bans.sweep(now);
+        }
+        info!(
+            self.logger,
+            "Banned {}{}",
+            endpoint.to_log(self.settings.log_endpoint),
+            if subnet { " subnet" } else { "" }
+        );
+        self.save();
+        true
+    }
+
+    pub fn unban(&self, endpoint: Endpoint, subnet: bool) -> bool {
+        let removed = {
+            let mut bans = self.bans.write().unwrap();
+            if subnet {
+                endpoint
+                    .subnet()
+                    .and_then(|subnet| bans.subnets.remove(&subnet))
+                    .is_some()
+            } else {
+                bans.hosts.remove(&endpoint.host()).is_some()
+            }
+        };
+        if removed {
+            self.save();
+        }
+        removed
+    }
+
+    pub fn map<R, F: Fn(Endpoint, bool, &Entry) -> R>(&self, f: F) -> Vec<R> {
+        let now = SystemClock::millis();
+        let bans = self.bans.read().unwrap();
+        let hosts = bans
+            .hosts
+            .iter()
+            .map(|(&endpoint, entry)| (endpoint, false, entry));
+        let subnets = bans
+            .subnets
+            .iter()
+            .map(|(&endpoint, entry)| (endpoint, true, entry));
+        hosts
+            .chain(subnets)
+            .filter(|(_, _, entry)| entry.is_active(now))
+            .map(|(endpoint, subnet, entry)| f(endpoint, subnet, entry))
+            .collect()
+    }
+
+    fn load(&self) -> Result<(), Box<dyn Error>> {
+        let mut bans = self.bans.write().unwrap();
+        let mut file = match File::open(self.state_dir.join(FILE_NAME)) {
+            Ok(file) => BufReader::new(file),
+            Err(err) => {
+                if err.kind() == ErrorKind::NotFound {
+                    // first run or unlinked file
+                    return Ok(());
+                } else {
+                    return Err(Box::new(err));
+                }
+            }
+        };
+        let mut version = [0u8; 4];
+        file.read_exact(&mut version)?;
+        let version = u32::from_be_bytes(version);
+        if version != FILE_VERSION {
+            warn!(self.logger, "Unknown {FILE_NAME} version {version}");
+            return Ok(());
+        }
+        *bans = from_read(&mut file)?;
+        bans.sweep(SystemClock::millis());
+        Ok(())
+    }
+
+    fn save(&self) {
+        let bans = self.bans.read().unwrap();
+        if let Err(err) = replace(&self.state_dir, FILE_NAME, |buffered| {
+            let version = FILE_VERSION.to_be_bytes();
+            buffered.write_all(&version)?;
+            to_write(&*bans, buffered)
+        }) {
+            error!(self.logger, "Can't write {FILE_NAME}: {err}");
+        }
+    }
+}
+
+#[derive(Default, Deserialize, Serialize)]
+struct Bans {
+    hosts: HashMap<Endpoint, Entry>,
+    subnets: HashMap<Endpoint, Entry>,
+}
+
+impl Bans {
+    fn sweep(&mut self, now: Milliseconds) {
+        self.hosts.retain(|_, entry| entry.is_active(now));
+        self.subnets.retain(|_, entry| entry.is_active(now));
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct Entry {
+    until: Milliseconds,
+    reason: String,
+}
+
+impl Entry {
+    fn is_active(&self, now: Milliseconds) -> bool {
+        now < self.until
+    }
+
+    pub const fn until(&self) -> Milliseconds {
+        self.until
+    }
+
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
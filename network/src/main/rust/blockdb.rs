@@ -19,6 +19,7 @@ use crate::coindb::{CoinDB, State, Update};
 use crate::dbview::DBView;
 use crate::fjall::Fjall;
 use crate::genesis;
+use crate::notifier::Notifier;
 use crate::rollinghashset::RollingHashSet;
 use arc_swap::ArcSwapOption;
 use blacknet_compat::{Mode, XDGDirectories, statvfs};
@@ -37,7 +38,7 @@ use serde::{Deserialize, Serialize};
 use std::fs::File;
 use std::io::{BufWriter, Write};
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, MutexGuard};
 
 const MIN_DISK_SPACE: u64 = MAX_BLOCK_SIZE as u64 * 2;
 
@@ -100,12 +101,14 @@ pub struct BlockDB {
     logger: Logger,
     cached_block: ArcSwapOption<(Hash, Box<[u8]>)>,
     cached_index: ArcSwapOption<(Hash, BlockIndex)>,
-    rejects: RollingHashSet<Hash>,
+    rejects: Mutex<RollingHashSet<Hash>>,
+    processing: Mutex<()>,
     blocks: DBView<Hash, Block>,
     pub(super) indexes: DBView<Hash, BlockIndex>,
     fjall: Arc<Fjall>,
     data_dir: PathBuf,
     requires_network: bool,
+    notifier: Arc<Notifier>,
 }
 
 impl BlockDB {
@@ -114,26 +117,36 @@ impl BlockDB {
         dirs: &XDGDirectories,
         fjall: Arc<Fjall>,
         log_manager: &LogManager,
+        notifier: Arc<Notifier>,
     ) -> Result<Arc<Self>, Box<dyn StdError>> {
         Ok(Arc::new(Self {
             logger: log_manager.logger("BlockDB")?,
             cached_block: ArcSwapOption::empty(),
             cached_index: ArcSwapOption::empty(),
-            rejects: RollingHashSet::new(ROLLBACK_LIMIT),
+            rejects: Mutex::new(RollingHashSet::new(ROLLBACK_LIMIT)),
+            processing: Mutex::new(()),
             blocks: DBView::with_blob(&fjall, "blocks")?,
             indexes: DBView::new(&fjall, "indexes")?,
             fjall,
             data_dir: dirs.data().to_owned(),
             requires_network: mode.requires_network(),
+            notifier,
         }))
     }
 
+    /**
+     * Serializes changes of the chain: block processing, rollbacks and wallet rescans
+     */
+    pub(crate) fn lock(&self) -> MutexGuard<'_, ()> {
+        self.processing.lock().unwrap()
+    }
+
     pub const fn cached_block(&self) -> &ArcSwapOption<(Hash, Box<[u8]>)> {
         &self.cached_block
     }
 
     pub fn is_rejected(&self, hash: Hash) -> bool {
-        self.rejects.contains(&hash)
+        self.rejects.lock().unwrap().contains(&hash)
     }
 
     pub fn contains(&self, hash: Hash) -> bool {
@@ -152,6 +165,14 @@ impl BlockDB {
         self.blocks.get_bytes(hash)
     }
 
+    pub fn delete(&self, hashes: &[Hash]) {
+        let mut batch = self.fjall.create_write_batch();
+        for &hash in hashes {
+            self.blocks.batch_remove(&mut batch, hash);
+        }
+        batch.commit().unwrap();
+    }
+
     pub fn next_block_hashes(&self, start: Hash, max: usize) -> Option<Vec<Hash>> {
         let mut index = self.indexes.get(start)?;
         let mut result = Vec::<Hash>::with_capacity(max);
@@ -277,22 +298,37 @@ impl BlockDB {
         check
     }
 
-    pub fn process(&mut self, hash: Hash, bytes: Box<[u8]>, state: &State) -> Result<()> {
-        if self.is_rejected(hash) {
+    /**
+     * Return hashes of transactions in the connected block
+     */
+    pub fn process(
+        &self,
+        hash: Hash,
+        bytes: Box<[u8]>,
+        coin_db: &Arc<CoinDB>,
+    ) -> Result<Vec<Hash>> {
+        let _processing = self.lock();
+        let mut rejects = self.rejects.lock().unwrap();
+        if rejects.contains(&hash) {
             return Err(Error::invalid("Already rejected block"));
         }
         if self.contains(hash) {
             return Err(Error::already_have(hash.to_string()));
         }
-        let result = self.process_block(hash, bytes, state);
+        let result = self.process_block(hash, bytes, coin_db);
         if matches!(result, Err(Error::Invalid(_))) {
-            self.rejects.insert(hash);
+            rejects.insert(hash);
         }
         result
     }
 
-    #[expect(unreachable_code, unused_variables)]
-    fn process_block(&self, hash: Hash, bytes: Box<[u8]>, state: &State) -> Result<()> {
+    fn process_block(
+        &self,
+        hash: Hash,
+        bytes: Box<[u8]>,
+        coin_db: &Arc<CoinDB>,
+    ) -> Result<Vec<Hash>> {
+        let state = coin_db.state();
         let block = from_bytes::<Block>(&bytes, false)?;
         if block.version() > BLOCK_VERSION {
             let percent = 100 * state.upgraded() / UPGRADE_THRESHOLD;
@@ -323,8 +359,8 @@ impl BlockDB {
             return Err(Error::not_reachable_vertex(block.previous().to_string()));
         }
         let batch = self.fjall.create_write_batch();
-        let coin_tx = Update::new(
-            todo!(),
+        let mut coin_tx = Update::new(
+            coin_db.clone(),
             batch,
             block.version(),
             hash,
@@ -333,15 +369,14 @@ impl BlockDB {
             bytes.len() as u32,
             block.generator(),
         );
-        let coin_db: CoinDB = todo!();
         let tx_hashes =
             coin_db.process_block_impl(&mut coin_tx, hash, &block, bytes.len() as u32)?;
-        self.blocks.batch_bytes(&mut batch, hash, &bytes);
+        self.blocks.batch_bytes(coin_tx.write_batch(), hash, &bytes);
         coin_tx.commit_impl();
-        //TODO RPC
+        self.notifier.block(hash, coin_db.state().height(), &bytes);
         self.cached_block
             .store(Some(Arc::new((block.previous(), bytes))));
-        Ok(())
+        Ok(tx_hashes)
     }
 }
 
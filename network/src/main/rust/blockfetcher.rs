@@ -17,58 +17,129 @@
 
 use crate::coindb::CoinDB;
 use crate::connection::Connection;
-use crate::packet::{BlockAnnounce, Blocks, ConsensusFault};
+use crate::node::Node;
+use crate::packet::{BlockAnnounce, Blocks, ConsensusFault, GetBlocks};
 use crate::settings::Settings;
 use blacknet_crypto::bigint::UInt256;
 use blacknet_kernel::blake2b::Hash;
-use blacknet_kernel::error::Result;
-use std::sync::Arc;
-use tokio::sync::mpsc;
+use blacknet_kernel::block::Block;
+use blacknet_kernel::error::{Error, Result};
+use blacknet_kernel::proofofstake::{ROLLBACK_LIMIT, guess_initial_synchronization};
+use blacknet_log::{LogManager, Logger, debug, error, info};
+use blacknet_time::SystemClock;
+use core::cmp::Ordering as CmpOrdering;
+use core::error::Error as StdError;
+use std::collections::BinaryHeap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use tokio::sync::{Notify, oneshot};
+use tokio::time::{Duration, timeout};
+
+type Request = oneshot::Sender<core::result::Result<Blocks, &'static str>>;
+
+enum Source {
+    Remote(Weak<Connection>, BlockAnnounce),
+    Deferred(Weak<Connection>, Blocks, UInt256),
+    Staked(Hash, Vec<u8>, oneshot::Sender<Result<usize>>),
+}
+
+impl Source {
+    const fn priority(&self) -> u8 {
+        match self {
+            Source::Remote(..) => 0,
+            Source::Deferred(..) => 1,
+            Source::Staked(..) => 2,
+        }
+    }
+}
+
+struct Rollback {
+    to: Hash,
+    // disconnected blocks and cumulative difficulty of the original chain
+    undo: Option<(Vec<Hash>, UInt256)>,
+}
+
+impl PartialEq for Source {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority() == other.priority()
+    }
+}
+
+impl Eq for Source {}
+
+impl PartialOrd for Source {
+    fn partial_cmp(&self, other: &Self) -> Option<CmpOrdering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Source {
+    fn cmp(&self, other: &Self) -> CmpOrdering {
+        self.priority().cmp(&other.priority())
+    }
+}
 
-#[expect(dead_code)]
 pub struct BlockFetcher {
-    connection_id: u64,
-    announces_receiver: mpsc::Receiver<(u64, BlockAnnounce)>,
-    announces_sender: mpsc::Sender<(u64, BlockAnnounce)>,
+    logger: Logger,
+    connection_id: AtomicU64,
+    sources: Mutex<BinaryHeap<Source>>,
+    sources_notify: Notify,
+    max_announces: usize,
+    request: Mutex<Option<Request>>,
     coin_db: Arc<CoinDB>,
 }
 
 impl BlockFetcher {
-    pub fn new(coin_db: Arc<CoinDB>, settings: &Arc<Settings>) -> Self {
+    pub fn new(
+        log_manager: &LogManager,
+        coin_db: Arc<CoinDB>,
+        settings: &Arc<Settings>,
+    ) -> Result<Self, Box<dyn StdError>> {
         let size = settings.incoming_connections as usize + settings.outgoing_connections as usize;
-        let (announces_sender, announces_receiver) = mpsc::channel(size);
-        Self {
-            connection_id: 0,
-            announces_receiver,
-            announces_sender,
+        Ok(Self {
+            logger: log_manager.logger("BlockFetcher")?,
+            connection_id: AtomicU64::new(0),
+            sources: Mutex::new(BinaryHeap::with_capacity(size)),
+            sources_notify: Notify::new(),
+            max_announces: size,
+            request: Mutex::new(None),
             coin_db,
-        }
+        })
     }
 
-    pub const fn is_synchronizing(&self) -> bool {
-        self.connection_id != 0
+    pub fn is_synchronizing(&self) -> bool {
+        self.connection_id.load(Ordering::Acquire) != 0
     }
 
     pub fn disconnected(&self, connection: &Connection) {
-        if self.connection_id != connection.id() {
+        if self.connection_id.load(Ordering::Acquire) != connection.id() {
             return;
         }
 
-        todo!();
+        self.cancel("Connection closed");
     }
 
-    pub fn offer(&self, connection: &Connection, block_announce: BlockAnnounce) {
+    pub fn offer(&self, connection: &Arc<Connection>, block_announce: BlockAnnounce) {
         if block_announce.cumulative_difficulty() <= self.coin_db.state().cumulative_difficulty() {
             return;
         }
 
-        let _ = self
-            .announces_sender
-            .try_send((connection.id(), block_announce));
+        let mut sources = self.sources.lock().unwrap();
+        if sources.len() >= self.max_announces {
+            return;
+        }
+        sources.push(Source::Remote(Arc::downgrade(connection), block_announce));
+        drop(sources);
+        self.sources_notify.notify_one();
     }
 
-    pub async fn staked_block(&self, _hash: Hash, _bytes: Vec<u8>) -> Result<usize> {
-        todo!();
+    pub async fn staked_block(&self, hash: Hash, bytes: Vec<u8>) -> Result<usize> {
+        let (sender, receiver) = oneshot::channel();
+        self.push(Source::Staked(hash, bytes, sender));
+        self.cancel("Staked new block");
+        receiver
+            .await
+            .unwrap_or_else(|_| Err(Error::invalid("BlockFetcher is shut down")))
     }
 
     pub fn consensus_fault(&self, connection: &Connection, _consensus_fault: ConsensusFault) {
@@ -79,14 +150,14 @@ impl BlockFetcher {
 
         connection.close();
 
-        if self.connection_id != connection.id() {
+        if self.connection_id.load(Ordering::Acquire) != connection.id() {
             return;
         }
 
-        todo!();
+        self.cancel("Dipath longer than the rolling checkpoint");
     }
 
-    pub fn blocks(&self, connection: &Connection, _blocks: Blocks) {
+    pub fn blocks(&self, connection: &Arc<Connection>, blocks: Blocks) {
         let requested_difficulty = connection.swap_requested_difficulty(UInt256::ZERO);
 
         if requested_difficulty == UInt256::ZERO {
@@ -94,6 +165,366 @@ impl BlockFetcher {
             return;
         }
 
-        todo!();
+        let request = if self.connection_id.load(Ordering::Acquire) == connection.id() {
+            self.request.lock().unwrap().take()
+        } else {
+            None
+        };
+        match request {
+            Some(request) => {
+                let _ = request.send(Ok(blocks));
+            }
+            None => self.push(Source::Deferred(
+                Arc::downgrade(connection),
+                blocks,
+                requested_difficulty,
+            )),
+        }
+    }
+
+    fn push(&self, source: Source) {
+        self.sources.lock().unwrap().push(source);
+        self.sources_notify.notify_one();
+    }
+
+    async fn take(&self) -> Source {
+        loop {
+            if let Some(source) = self.sources.lock().unwrap().pop() {
+                return source;
+            }
+            self.sources_notify.notified().await;
+        }
+    }
+
+    fn cancel(&self, reason: &'static str) {
+        if let Some(request) = self.request.lock().unwrap().take() {
+            let _ = request.send(Err(reason));
+        }
+    }
+
+    pub async fn implementation(weak: Weak<Node>) {
+        loop {
+            let node = match weak.upgrade() {
+                Some(node) => node,
+                None => break,
+            };
+            let block_fetcher = node.block_fetcher();
+            match block_fetcher.take().await {
+                Source::Staked(hash, bytes, sender) => {
+                    let result = block_fetcher.process_staked(&node, hash, bytes);
+                    let _ = sender.send(result);
+                }
+                Source::Deferred(connection, answer, requested_difficulty) => {
+                    let state = block_fetcher.coin_db.state();
+                    if let Some(connection) = connection.upgrade()
+                        && requested_difficulty > state.cumulative_difficulty()
+                    {
+                        block_fetcher.process_deferred(&node, &connection, answer);
+                    }
+                }
+                Source::Remote(connection, block_announce) => {
+                    if let Some(connection) = block_fetcher.best_connection(&node, &connection) {
+                        block_fetcher
+                            .fetch(&node, &connection, block_announce.cumulative_difficulty())
+                            .await;
+                    }
+                }
+            }
+        }
+    }
+
+    fn process_staked(&self, node: &Node, hash: Hash, bytes: Vec<u8>) -> Result<usize> {
+        node.process_block(hash, bytes.into_boxed_slice())?;
+        let state = self.coin_db.state();
+        Ok(node.announce_block(hash, state.cumulative_difficulty(), None))
+    }
+
+    // Prefer the peer that has announced the heaviest chain
+    fn best_connection(
+        &self,
+        node: &Node,
+        announcer: &Weak<Connection>,
+    ) -> Option<Arc<Connection>> {
+        let cumulative_difficulty = self.coin_db.state().cumulative_difficulty();
+        let connections = node.connections().read().unwrap();
+        let best = connections
+            .iter()
+            .filter(|connection| connection.is_established() && !connection.requested_blocks())
+            .max_by_key(|connection| connection.last_block().load().cumulative_difficulty())
+            .cloned()
+            .or_else(|| announcer.upgrade())?;
+        drop(connections);
+        if best.requested_blocks()
+            || best.last_block().load().cumulative_difficulty() <= cumulative_difficulty
+        {
+            return None;
+        }
+        Some(best)
+    }
+
+    async fn fetch(&self, node: &Node, connection: &Arc<Connection>, announced: UInt256) {
+        let block_db = node.block_db();
+        let last_block = connection.last_block().load_full();
+        if block_db.is_rejected(last_block.hash()) {
+            connection.dos("Rejected block");
+            return;
+        }
+        let announced = announced.max(last_block.cumulative_difficulty());
+
+        info!(self.logger, "Fetching {}", last_block.hash());
+        self.connection_id.store(connection.id(), Ordering::Release);
+        let mut state = self.coin_db.state();
+        let original_chain = state.block_hash();
+        let mut connected_blocks = 0;
+        let mut rollback: Option<Rollback> = None;
+
+        let mut request = self.request_blocks(
+            connection,
+            state.block_hash(),
+            state.rolling_checkpoint(),
+            announced,
+        );
+        'request: loop {
+            let answer = match timeout(self.timeout(), request).await {
+                Ok(Ok(Ok(answer))) => answer,
+                Ok(Ok(Err(reason))) => {
+                    info!(self.logger, "Fetching cancelled: {reason}");
+                    break;
+                }
+                Ok(Err(_)) => break,
+                Err(_) => {
+                    // stalled peer
+                    connection.dos("Fetching cancelled: Request timed out");
+                    break;
+                }
+            };
+            if !answer.blocks().is_empty() {
+                match self.process_blocks(node, connection, answer, &mut rollback) {
+                    Some(n) => connected_blocks += n,
+                    None => break,
+                }
+
+                state = self.coin_db.state();
+
+                if announced > state.cumulative_difficulty() {
+                    request = self.request_blocks(
+                        connection,
+                        state.block_hash(),
+                        state.rolling_checkpoint(),
+                        announced,
+                    );
+                } else {
+                    break;
+                }
+            } else if !answer.hashes().is_empty() {
+                if rollback.is_some() || connected_blocks != 0 {
+                    connection.dos("Unexpected rollback");
+                    break;
+                }
+                let mut prev = state.rolling_checkpoint();
+                for &hash in answer.hashes() {
+                    if block_db.is_rejected(hash) {
+                        connection.dos("Rejected block");
+                        break 'request;
+                    }
+                    let block_index = match block_db.index(hash) {
+                        Some(block_index) => block_index,
+                        None => break,
+                    };
+                    if block_index.height() + (ROLLBACK_LIMIT as u32) < state.height() {
+                        connection.dos(&format!("Rollback to {}", block_index.height()));
+                        break 'request;
+                    }
+                    prev = hash;
+                }
+                rollback = Some(Rollback {
+                    to: prev,
+                    undo: None,
+                });
+                request =
+                    self.request_blocks(connection, prev, state.rolling_checkpoint(), announced);
+            } else {
+                break;
+            }
+        }
+
+        if let Some(Rollback {
+            to,
+            undo: Some((disconnected, undo_difficulty)),
+        }) = rollback
+        {
+            state = self.coin_db.state();
+            if undo_difficulty >= state.cumulative_difficulty() {
+                info!(self.logger, "Reconnecting {} blocks", disconnected.len());
+                let to_remove = self.coin_db.undo_rollback(to, &disconnected);
+                node.blocks_disconnected(&to_remove);
+                node.blocks_reconnected(&disconnected);
+                block_db.delete(&to_remove);
+            } else {
+                debug!(
+                    self.logger,
+                    "Deleting {} blocks from db",
+                    disconnected.len()
+                );
+                block_db.delete(&disconnected);
+            }
+        }
+
+        state = self.coin_db.state();
+        if state.block_hash() != original_chain {
+            node.announce_block(
+                state.block_hash(),
+                state.cumulative_difficulty(),
+                Some(connection.id()),
+            );
+            connection.set_last_block_time(connection.last_packet_time());
+        }
+
+        if connection.is_closed() {
+            info!(
+                self.logger,
+                "Fetched {connected_blocks} blocks from disconnected {}",
+                connection.id()
+            );
+        } else {
+            info!(
+                self.logger,
+                "Fetched {connected_blocks} blocks from {}",
+                connection.id()
+            );
+        }
+
+        self.request.lock().unwrap().take();
+        self.connection_id.store(0, Ordering::Release);
+    }
+
+    fn process_blocks(
+        &self,
+        node: &Node,
+        connection: &Connection,
+        answer: Blocks,
+        rollback: &mut Option<Rollback>,
+    ) -> Option<usize> {
+        if let Some(rollback) = rollback
+            && rollback.undo.is_none()
+        {
+            let undo_difficulty = self.coin_db.state().cumulative_difficulty();
+            match self.coin_db.rollback_to(rollback.to) {
+                Ok(disconnected) => {
+                    node.blocks_disconnected(&disconnected);
+                    rollback.undo = Some((disconnected, undo_difficulty));
+                }
+                Err(err) => {
+                    connection.dos(&err.to_string());
+                    return None;
+                }
+            }
+        }
+        let disconnected = match rollback {
+            Some(Rollback {
+                undo: Some((disconnected, _)),
+                ..
+            }) => disconnected.as_slice(),
+            _ => &[],
+        };
+        let blocks = answer.into_blocks();
+        let n = blocks.len();
+        for bytes in blocks {
+            let hash = match Block::compute_hash(&bytes) {
+                Some(hash) => hash,
+                None => {
+                    connection.dos("Invalid block");
+                    return None;
+                }
+            };
+            if disconnected.contains(&hash) {
+                connection.dos(&format!("Rollback contains {hash}"));
+                return None;
+            }
+            if let Err(err) = node.process_block(hash, bytes) {
+                connection.dos(&err.to_string());
+                return None;
+            }
+        }
+        if rollback.is_none() {
+            self.coin_db.prune();
+        }
+        if n >= 10 {
+            info!(self.logger, "Connected {n} blocks");
+        }
+        Some(n)
+    }
+
+    // Blocks were received after timeout. During lags, processing these helps to stay in sync.
+    fn process_deferred(&self, node: &Node, connection: &Connection, answer: Blocks) {
+        if !answer.blocks().is_empty() {
+            info!(
+                self.logger,
+                "Mongering {} deferred blocks from {}",
+                answer.blocks().len(),
+                connection.id()
+            );
+            for bytes in answer.into_blocks() {
+                let hash = match Block::compute_hash(&bytes) {
+                    Some(hash) => hash,
+                    None => {
+                        connection.dos("Invalid block");
+                        break;
+                    }
+                };
+                match node.process_block(hash, bytes) {
+                    Ok(()) => {
+                        // Continue catching up
+                        info!(self.logger, "Accepted {hash}");
+                    }
+                    Err(Error::AlreadyHave(_)) => {
+                        // Perhaps sequent blocks will be useful
+                        debug!(self.logger, "AlreadyHave {hash}");
+                    }
+                    Err(err @ (Error::InFuture(_) | Error::Invalid(_))) => {
+                        // No way
+                        connection.dos(&err.to_string());
+                        break;
+                    }
+                    Err(Error::NotReachableVertex(_)) => {
+                        debug!(self.logger, "NotReachableVertex {hash}");
+                        break;
+                    }
+                }
+            }
+        } else if !answer.hashes().is_empty() {
+            debug!(
+                self.logger,
+                "Skipped {} deferred hashes",
+                answer.hashes().len()
+            );
+        } else {
+            // Must not happen
+            error!(self.logger, "Invalid packet Blocks");
+        }
+    }
+
+    fn request_blocks(
+        &self,
+        connection: &Connection,
+        hash: Hash,
+        checkpoint: Hash,
+        difficulty: UInt256,
+    ) -> oneshot::Receiver<core::result::Result<Blocks, &'static str>> {
+        let (sender, receiver) = oneshot::channel();
+        *self.request.lock().unwrap() = Some(sender);
+        connection.swap_requested_difficulty(difficulty);
+        connection.send_packet(&GetBlocks::new(hash, checkpoint));
+        receiver
+    }
+
+    fn timeout(&self) -> Duration {
+        let state = self.coin_db.state();
+        let pos_version = state.pos_version(self.coin_db.requires_network());
+        if !guess_initial_synchronization(pos_version, SystemClock::secs(), state.block_time()) {
+            Duration::from_secs(4)
+        } else {
+            Duration::from_secs(10)
+        }
     }
 }
@@ -0,0 +1,121 @@
+/*
+ * Copyright (c) 2026 Pavel Vasin
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Lesser General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::node::Node;
+use blacknet_kernel::blake2b::Hash;
+use blacknet_kernel::block::Block;
+use blacknet_kernel::error::Error;
+use blacknet_kernel::proofofstake::MAX_BLOCK_SIZE;
+use blacknet_log::{Logger, debug, error, info, warn};
+use core::error::Error as StdError;
+use std::fs::{File, rename};
+use std::io::{BufReader, ErrorKind, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Weak;
+
+pub const FILE_NAME: &str = "bootstrap.dat";
+const PROGRESS_INTERVAL: u32 = 10000;
+
+/**
+ * Imports blocks written by `BlockDB::export`.
+ * Blocks that are already known are skipped, so an interrupted import resumes from where it stopped.
+ */
+pub(crate) fn import(logger: &Logger, node: &Weak<Node>, path: &Path) {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) => {
+            if err.kind() != ErrorKind::NotFound {
+                warn!(logger, "Can't open {}: {err}", path.display());
+            }
+            return;
+        }
+    };
+    info!(logger, "Importing {}", path.display());
+    match import_impl(logger, node, file) {
+        Ok(Some((imported, skipped))) => {
+            info!(
+                logger,
+                "Imported {imported} blocks, skipped {skipped} known blocks"
+            );
+            let mut old = PathBuf::from(path);
+            old.as_mut_os_string().push(".old");
+            if let Err(err) = rename(path, &old) {
+                error!(logger, "Can't rename {}: {err}", path.display());
+            }
+        }
+        Ok(None) => info!(logger, "Import interrupted"),
+        Err(err) => error!(logger, "Import stopped: {err}"),
+    }
+}
+
+// None on shutdown
+fn import_impl(
+    logger: &Logger,
+    node: &Weak<Node>,
+    file: File,
+) -> Result<Option<(u32, u32)>, Box<dyn StdError>> {
+    let total = file.metadata()?.len();
+    let mut buffered = BufReader::new(file);
+    let mut offset = 0u64;
+    let mut imported: u32 = 0;
+    let mut skipped: u32 = 0;
+    loop {
+        let mut size = [0u8; 4];
+        match buffered.read_exact(&mut size) {
+            Ok(()) => (),
+            Err(err) if err.kind() == ErrorKind::UnexpectedEof => break,
+            Err(err) => return Err(err.into()),
+        }
+        let size = u32::from_be_bytes(size);
+        if size > MAX_BLOCK_SIZE {
+            return Err(format!("Too long block {size} at offset {offset}").into());
+        }
+        let mut bytes = vec![0; size as usize];
+        buffered.read_exact(&mut bytes)?;
+        offset += 4 + size as u64;
+
+        let Some(node) = node.upgrade() else {
+            return Ok(None);
+        };
+        let hash: Hash = Block::compute_hash(&bytes)
+            .ok_or_else(|| format!("Invalid block at offset {offset}"))?;
+        match node.process_block(hash, bytes.into_boxed_slice()) {
+            Ok(()) => imported += 1,
+            Err(Error::AlreadyHave(_)) => {
+                if skipped == 0 && imported == 0 {
+                    debug!(logger, "Skipping known blocks");
+                }
+                skipped += 1;
+            }
+            Err(err) => return Err(format!("{err} at offset {offset}").into()),
+        }
+        if (imported + skipped).is_multiple_of(PROGRESS_INTERVAL) {
+            node.coin_db().prune();
+            info!(
+                logger,
+                "Processed {} blocks, height {} ({}%)",
+                imported + skipped,
+                node.coin_db().state().height(),
+                offset * 100 / total.max(1)
+            );
+        }
+    }
+    if let Some(node) = node.upgrade() {
+        node.coin_db().prune();
+    }
+    Ok(Some((imported, skipped)))
+}
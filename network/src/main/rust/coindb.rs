@@ -15,11 +15,16 @@
  * along with this program. If not, see <https://www.gnu.org/licenses/>.
  */
 
+use crate::addressindex::AddressIndex;
 use crate::blockdb::{BlockDB, BlockIndex};
 use crate::dbview::DBView;
 use crate::fjall::Fjall;
 use crate::genesis;
+use crate::settings::Settings;
+use crate::txindex::TxIndex;
 use crate::undoblock::UndoBlock;
+use crate::walletsync;
+use arc_swap::ArcSwap;
 use blacknet_compat::Mode;
 use blacknet_crypto::bigint::UInt256;
 use blacknet_kernel::account::Account;
@@ -38,9 +43,11 @@ use blacknet_kernel::proofofstake::{
 use blacknet_kernel::transaction::{
     CoinTx, HashTimeLockContractId, MultiSignatureLockContractId, Transaction,
 };
-use blacknet_log::{LogManager, Logger, error};
+use blacknet_log::{LogManager, Logger, error, info};
 use blacknet_serialization::format::{from_bytes, to_bytes};
 use blacknet_time::Seconds;
+use blacknet_wallet::scanner::Contracts;
+use blacknet_wallet::walletdb::WalletDB;
 use core::cmp::{max, min};
 use core::error::Error as StdError;
 use fjall::OwnedWriteBatch as WriteBatch;
@@ -48,34 +55,191 @@ use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque, hash_map};
 use std::sync::Arc;
 
+const STATE_KEY: &str = "state";
+const REINDEX_PROGRESS_INTERVAL: u32 = 10000;
+
 pub struct CoinDB {
     logger: Logger,
-    state: State,
+    state: ArcSwap<State>,
     accounts: DBView<PublicKey, Account>,
     htlcs: DBView<HashTimeLockContractId, HTLC>,
     multisigs: DBView<MultiSignatureLockContractId, Multisig>,
+    undos: DBView<Hash, UndoBlock>,
+    states: DBView<&'static str, State>,
     block_db: Arc<BlockDB>,
+    fjall: Arc<Fjall>,
+    requires_network: bool,
+    address_index: Option<AddressIndex>,
+    tx_index: Option<TxIndex>,
+    wallet_db: Arc<WalletDB>,
 }
 
 impl CoinDB {
     pub fn new(
         mode: &Mode,
-        fjall: &Fjall,
+        fjall: &Arc<Fjall>,
         block_db: Arc<BlockDB>,
         log_manager: &LogManager,
+        settings: &Settings,
+        wallet_db: Arc<WalletDB>,
     ) -> core::result::Result<Arc<Self>, Box<dyn StdError>> {
-        Ok(Arc::new(Self {
+        let coin_db = Self {
             logger: log_manager.logger("CoinDB")?,
-            state: State::genesis(mode), //TODO
+            state: ArcSwap::from_pointee(State::genesis(mode)),
             accounts: DBView::new(fjall, "accounts")?,
             htlcs: DBView::new(fjall, "htlcs")?,
             multisigs: DBView::new(fjall, "multisigs")?,
+            undos: DBView::new(fjall, "undos")?,
+            states: DBView::new(fjall, "coindb")?,
             block_db,
-        }))
+            fjall: fjall.clone(),
+            requires_network: mode.requires_network(),
+            address_index: if settings.address_index {
+                Some(AddressIndex::new(fjall)?)
+            } else {
+                None
+            },
+            tx_index: if settings.tx_index {
+                Some(TxIndex::new(fjall)?)
+            } else {
+                None
+            },
+            wallet_db,
+        };
+        match coin_db.states.get(STATE_KEY) {
+            Some(state) => {
+                coin_db.check_state(&state)?;
+                info!(coin_db.logger, "Consensus height {}", state.height);
+                coin_db.state.store(Arc::new(state));
+            }
+            None => coin_db.load_genesis(mode)?,
+        }
+        coin_db
+            .wallet_db
+            .set_anchor(coin_db.state.load().rolling_checkpoint);
+        let tip = coin_db.state.load().block_hash;
+        if let Some(address_index) = &coin_db.address_index
+            && !address_index.is_synchronized(tip)
+        {
+            coin_db.reindex(
+                "address index",
+                |batch| address_index.clear(batch),
+                |batch, hash, height, block| address_index.connect(batch, hash, height, block),
+            );
+        }
+        if let Some(tx_index) = &coin_db.tx_index
+            && !tx_index.is_synchronized(tip)
+        {
+            coin_db.reindex(
+                "tx index",
+                |batch| tx_index.clear(batch),
+                |batch, hash, _, block| tx_index.connect(batch, hash, block),
+            );
+        }
+        walletsync::synchronize(
+            &coin_db.logger,
+            &coin_db.block_db,
+            &coin_db,
+            &coin_db.wallet_db,
+        );
+        Ok(Arc::new(coin_db))
+    }
+
+    fn check_state(&self, state: &State) -> core::result::Result<(), Box<dyn StdError>> {
+        match self.block_db.indexes.get(state.block_hash) {
+            Some(block_index)
+                if block_index.height() == state.height && block_index.next() == Hash::ZERO => {}
+            Some(block_index) => {
+                return Err(format!(
+                    "CoinDB tip {} at height {} disagrees with BlockDB index at height {}",
+                    state.block_hash,
+                    state.height,
+                    block_index.height()
+                )
+                .into());
+            }
+            None => {
+                return Err(format!(
+                    "CoinDB tip {} at height {} is missing in BlockDB indexes",
+                    state.block_hash, state.height
+                )
+                .into());
+            }
+        }
+        if !self.block_db.indexes.contains(state.rolling_checkpoint) {
+            return Err(format!(
+                "CoinDB rolling checkpoint {} is missing in BlockDB indexes",
+                state.rolling_checkpoint
+            )
+            .into());
+        }
+        Ok(())
+    }
+
+    fn load_genesis(&self, mode: &Mode) -> fjall::Result<()> {
+        let mut batch = self.fjall.create_write_batch();
+        for (public_key, balance) in genesis::balances(mode) {
+            let account = Account::with_stake(balance);
+            self.accounts.batch(&mut batch, public_key, &account);
+        }
+        let block_index = BlockIndex::new(Hash::ZERO, Hash::ZERO, 0, 0, Amount::ZERO);
+        self.block_db
+            .indexes
+            .batch(&mut batch, genesis::hash(), &block_index);
+        self.states.batch(&mut batch, STATE_KEY, &self.state.load());
+        batch.commit()
+    }
+
+    pub fn state(&self) -> Arc<State> {
+        self.state.load_full()
     }
 
-    pub const fn state(&self) -> &State {
-        &self.state
+    pub const fn requires_network(&self) -> bool {
+        self.requires_network
+    }
+
+    pub const fn address_index(&self) -> Option<&AddressIndex> {
+        self.address_index.as_ref()
+    }
+
+    pub const fn tx_index(&self) -> Option<&TxIndex> {
+        self.tx_index.as_ref()
+    }
+
+    /**
+     * Fill an optional index from genesis up to the current state
+     */
+    fn reindex(
+        &self,
+        name: &str,
+        clear: impl FnOnce(&mut WriteBatch),
+        connect: impl Fn(&mut WriteBatch, Hash, u32, &Block),
+    ) {
+        info!(self.logger, "Rebuilding {name}");
+        let mut batch = self.fjall.create_write_batch();
+        clear(&mut batch);
+        batch.commit().unwrap();
+
+        let tip = self.state.load().block_hash;
+        let mut hash = genesis::hash();
+        let mut height = 0;
+        while hash != tip {
+            hash = self
+                .block_db
+                .indexes
+                .get(hash)
+                .expect("consistent block index")
+                .next();
+            height += 1;
+            let (block, _) = self.block_db.get(hash).expect("consistent block db");
+            let mut batch = self.fjall.create_write_batch();
+            connect(&mut batch, hash, height, &block);
+            batch.commit().unwrap();
+            if height.is_multiple_of(REINDEX_PROGRESS_INTERVAL) {
+                info!(self.logger, "Rebuilding {name} at height {height}");
+            }
+        }
+        info!(self.logger, "Rebuilt {name} up to height {height}");
     }
 
     pub fn account(&self, public_key: PublicKey) -> Option<Account> {
@@ -91,7 +255,7 @@ impl CoinDB {
     }
 
     pub fn warnings(&self, warnings: &mut Vec<String>) {
-        if self.state.upgraded >= UPGRADE_THRESHOLD / 2 {
+        if self.state.load().upgraded >= UPGRADE_THRESHOLD / 2 {
             warnings.push("This version is obsolete, upgrade required!".to_owned())
         }
     }
@@ -102,7 +266,7 @@ impl CoinDB {
             accounts: 0,
             htlcs: 0,
             multisigs: 0,
-            expected_supply: self.state.supply,
+            expected_supply: self.state.load().supply,
             actual_supply: Amount::ZERO,
         };
         for (_, account) in self.accounts.iter() {
@@ -132,22 +296,23 @@ impl CoinDB {
     }
 
     fn next_rolling_checkpoint(&self) -> Hash {
-        if self.state.rolling_checkpoint != genesis::hash() {
+        let state = self.state.load();
+        if state.rolling_checkpoint != genesis::hash() {
             let block_index = self
                 .block_db
                 .indexes
-                .get(self.state.rolling_checkpoint)
+                .get(state.rolling_checkpoint)
                 .expect("consistent block index");
             block_index.next()
         } else {
-            if self.state.height < ROLLBACK_LIMIT as u32 + 1 {
+            if state.height < ROLLBACK_LIMIT as u32 + 1 {
                 return genesis::hash();
             }
-            let checkpoint = self.state.height - ROLLBACK_LIMIT as u32;
+            let checkpoint = state.height - ROLLBACK_LIMIT as u32;
             let mut block_index = self
                 .block_db
                 .indexes
-                .get(self.state.block_hash)
+                .get(state.block_hash)
                 .expect("consistent block index");
             while block_index.height() != checkpoint + 1 {
                 block_index = self
@@ -160,7 +325,205 @@ impl CoinDB {
         }
     }
 
-    #[expect(unused)]
+    fn undo_block(&self) -> Hash {
+        let state = self.state.load_full();
+        let hash = state.block_hash;
+        let block_index = self
+            .block_db
+            .indexes
+            .get(hash)
+            .expect("consistent block index");
+        let undo = self.undos.get(hash).expect("consistent undo block");
+
+        let height = state.height - 1;
+        let mut block_sizes = state.block_sizes.clone();
+        block_sizes.pop_back();
+        if block_sizes.len() < min(height as usize + 1, BLOCK_SIZE_SPAN) {
+            block_sizes.push_front(undo.block_size());
+        }
+        let new_state = State {
+            height,
+            block_hash: block_index.previous(),
+            block_time: undo.block_time(),
+            difficulty: undo.difficulty(),
+            cumulative_difficulty: undo.cumulative_difficulty(),
+            supply: undo.supply(),
+            nxtrng: undo.nxtrng(),
+            rolling_checkpoint: undo.rolling_checkpoint(),
+            max_block_size: max_block_size(&block_sizes),
+            upgraded: undo.upgraded(),
+            fork_v2: undo.fork_v2(),
+            block_sizes,
+        };
+
+        let mut batch = self.fjall.create_write_batch();
+        let mut prev_index = self
+            .block_db
+            .indexes
+            .get(block_index.previous())
+            .expect("consistent block index");
+        prev_index.set_next(Hash::ZERO);
+        prev_index.set_next_size(0);
+        self.block_db
+            .indexes
+            .batch(&mut batch, block_index.previous(), &prev_index);
+        self.block_db.indexes.batch_remove(&mut batch, hash);
+        if self.address_index.is_some() || self.tx_index.is_some() {
+            let (block, _) = self.block_db.get(hash).expect("consistent block db");
+            if let Some(address_index) = &self.address_index {
+                address_index.disconnect(&mut batch, state.height, &block);
+            }
+            if let Some(tx_index) = &self.tx_index {
+                tx_index.disconnect(&mut batch, &block);
+            }
+        }
+        for (key, bytes) in undo.accounts().iter().rev() {
+            match bytes {
+                Some(bytes) => self.accounts.batch_bytes(&mut batch, *key, bytes),
+                None => self.accounts.batch_remove(&mut batch, *key),
+            }
+        }
+        for (id, bytes) in undo.htlcs().iter().rev() {
+            match bytes {
+                Some(bytes) => self.htlcs.batch_bytes(&mut batch, *id, bytes),
+                None => self.htlcs.batch_remove(&mut batch, *id),
+            }
+        }
+        for (id, bytes) in undo.multisigs().iter().rev() {
+            match bytes {
+                Some(bytes) => self.multisigs.batch_bytes(&mut batch, *id, bytes),
+                None => self.multisigs.batch_remove(&mut batch, *id),
+            }
+        }
+        self.undos.batch_remove(&mut batch, hash);
+        self.states.batch(&mut batch, STATE_KEY, &new_state);
+        batch.commit().unwrap();
+
+        self.wallet_db.set_anchor(new_state.rolling_checkpoint);
+        self.state.store(Arc::new(new_state));
+        self.block_db.cached_block().store(None);
+        self.wallet_db
+            .disconnect(block_index.previous(), state.height - 1);
+
+        hash
+    }
+
+    fn rollback_to_impl(&self, hash: Hash) -> Vec<Hash> {
+        let mut result = Vec::new();
+        while self.state.load().block_hash != hash {
+            result.push(self.undo_block());
+        }
+        result
+    }
+
+    /**
+     * Disconnect blocks down to `hash` and return their hashes, the tip first
+     */
+    pub fn rollback_to(&self, hash: Hash) -> Result<Vec<Hash>> {
+        let _processing = self.block_db.lock();
+        let state = self.state.load_full();
+        let block_index = match self.block_db.indexes.get(hash) {
+            Some(block_index) => block_index,
+            None => return Err(Error::not_reachable_vertex(hash.to_string())),
+        };
+        if block_index.height() >= state.height {
+            return Err(Error::invalid(format!(
+                "Rollback to height {} from {}",
+                block_index.height(),
+                state.height
+            )));
+        }
+        if state.height - block_index.height() > ROLLBACK_LIMIT as u32 {
+            return Err(Error::invalid(format!(
+                "Rollback to height {} exceeds limit",
+                block_index.height()
+            )));
+        }
+        if state.rolling_checkpoint != genesis::hash() {
+            let checkpoint = self
+                .block_db
+                .indexes
+                .get(state.rolling_checkpoint)
+                .expect("consistent block index");
+            if block_index.height() < checkpoint.height() {
+                return Err(Error::invalid(format!(
+                    "Rollback to height {} behind rolling checkpoint",
+                    block_index.height()
+                )));
+            }
+        }
+        let result = self.rollback_to_impl(hash);
+        info!(self.logger, "Disconnected {} blocks", result.len());
+        Ok(result)
+    }
+
+    /**
+     * Reconnect blocks of the original chain, return hashes of the disconnected fork
+     */
+    pub fn undo_rollback(self: &Arc<Self>, rollback_to: Hash, list: &[Hash]) -> Vec<Hash> {
+        let _processing = self.block_db.lock();
+        let to_remove = if self.state.load().block_hash != rollback_to {
+            self.rollback_to_impl(rollback_to)
+        } else {
+            Vec::new()
+        };
+
+        for &hash in list.iter().rev() {
+            let (block, size) = match self.block_db.get(hash) {
+                Some(block) => block,
+                None => {
+                    error!(self.logger, "{hash} not found");
+                    return to_remove;
+                }
+            };
+            let mut coin_tx = Update::new(
+                self.clone(),
+                self.fjall.create_write_batch(),
+                block.version(),
+                hash,
+                block.previous(),
+                block.time(),
+                size as u32,
+                block.generator(),
+            );
+            if let Err(err) = self.process_block_impl(&mut coin_tx, hash, &block, size as u32) {
+                error!(self.logger, "{err} block {hash}");
+                return to_remove;
+            }
+            coin_tx.commit_impl();
+        }
+
+        to_remove
+    }
+
+    /**
+     * Delete undo blocks behind the rolling checkpoint
+     */
+    pub fn prune(&self) {
+        let mut batch = self.fjall.create_write_batch();
+        let mut block_index = self
+            .block_db
+            .indexes
+            .get(self.state.load().rolling_checkpoint)
+            .expect("consistent block index");
+        loop {
+            let hash = block_index.previous();
+            if !self.undos.contains(hash) {
+                break;
+            }
+            self.undos.batch_remove(&mut batch, hash);
+            if hash == Hash::ZERO {
+                break;
+            }
+            block_index = self
+                .block_db
+                .indexes
+                .get(hash)
+                .expect("consistent block index");
+        }
+        batch.commit().unwrap();
+    }
+
     pub fn process_block_impl(
         &self,
         coin_tx: &mut Update,
@@ -168,55 +531,57 @@ impl CoinDB {
         block: &Block,
         size: u32,
     ) -> Result<Vec<Hash>> {
-        if block.previous() != self.state.block_hash {
+        let state = self.state.load();
+        if block.previous() != state.block_hash {
             error!(
                 self.logger,
                 "{hash} not adjacent to {} edge {}",
-                self.state.block_hash,
+                state.block_hash,
                 block.previous()
             );
             return Err(Error::not_reachable_vertex(block.previous().to_string()));
         }
-        if size > self.state.max_block_size {
+        if size > state.max_block_size {
             return Err(Error::invalid(format!(
                 "Too large block {size} bytes, maximum {}",
-                self.state.max_block_size
+                state.max_block_size
             )));
         }
-        if block.time() <= self.state.block_time {
+        if block.time() <= state.block_time {
             return Err(Error::invalid("Timestamp is too early"));
         }
         let mut generator = coin_tx.get_account(block.generator())?;
         let height = coin_tx.height();
-        let tx_hashes = Vec::<Hash>::with_capacity(block.raw_transactions().len());
-        let pos_version = self.state.pos_version(todo!());
+        let mut tx_hashes = Vec::<Hash>::with_capacity(block.raw_transactions().len());
+        let pos_version = state.pos_version(self.requires_network);
 
         verify_pos(
             pos_version,
             block.time(),
             block.generator(),
-            self.state.nxtrng(),
-            self.state.difficulty(),
-            self.state.block_time(),
+            state.nxtrng(),
+            state.difficulty(),
+            state.block_time(),
             generator.staking_balance(height),
         )?;
 
         coin_tx.set_account(block.generator(), generator);
 
         let mut fees = Amount::ZERO;
+        let mut scanner = self.wallet_db.scanner();
         for tx_bytes in block.raw_transactions() {
             let tx = from_bytes::<Transaction>(tx_bytes, false)?;
             let tx_hash = Transaction::compute_hash(tx_bytes).expect("Hashable tx");
+            let effects = scanner.transaction(&*coin_tx, &tx, tx_hash, Some(height), block.time());
             coin_tx.process_transaction_impl(&tx, tx_hash)?;
+            scanner.record(effects, tx_bytes);
             tx_hashes.push(tx_hash);
             fees += tx.fee();
-
-            //TODO WalletDB
         }
 
         generator = coin_tx.get_account(block.generator())?;
 
-        let mint = mint(pos_version, self.state.supply);
+        let mint = mint(pos_version, state.supply);
         let generated = mint + fees;
 
         let mut prev_index = self
@@ -239,7 +604,16 @@ impl CoinDB {
         generator.debit(height, generated);
         coin_tx.set_account(block.generator(), generator);
 
-        //TODO WalletDB
+        // block has passed validation, a crash before commit of CoinDB is recovered on startup
+        scanner.generated(hash, height, block, generated);
+        self.wallet_db.commit(&mut scanner, Some((hash, height)));
+
+        if let Some(address_index) = &self.address_index {
+            address_index.connect(coin_tx.write_batch(), hash, height, block);
+        }
+        if let Some(tx_index) = &self.tx_index {
+            tx_index.connect(coin_tx.write_batch(), hash, block);
+        }
 
         Ok(tx_hashes)
     }
@@ -340,7 +714,6 @@ impl State {
     }
 }
 
-#[expect(unused)]
 pub struct Update {
     coin_db: Arc<CoinDB>,
     write_batch: WriteBatch,
@@ -373,7 +746,7 @@ impl Update {
         block_size: u32,
         block_generator: PublicKey,
     ) -> Self {
-        let state = coin_db.state().clone();
+        let state = State::clone(&coin_db.state.load());
         let height = state.height() + 1;
         let supply = state.supply();
         let rolling_checkpoint = coin_db.next_rolling_checkpoint();
@@ -410,14 +783,17 @@ impl Update {
         }
     }
 
+    pub const fn write_batch(&mut self) -> &mut WriteBatch {
+        &mut self.write_batch
+    }
+
     pub fn commit_impl(mut self) {
         if self.state.block_sizes.len() == BLOCK_SIZE_SPAN {
             self.state.block_sizes.pop_front();
         }
         self.state.block_sizes.push_back(self.block_size);
 
-        #[expect(unreachable_code, unused_variables)]
-        let pos_version = self.state.pos_version(todo!());
+        let pos_version = self.state.pos_version(self.coin_db.requires_network);
         let difficulty = next_difficulty(
             pos_version,
             self.undo.difficulty(),
@@ -452,7 +828,59 @@ impl Update {
             fork_v2,
             block_sizes: self.state.block_sizes,
         };
-        todo!();
+
+        let coin_db = &self.coin_db;
+        let batch = &mut self.write_batch;
+        let prev_index = self.prev_index.expect("previous block index");
+        let block_index = self.block_index.expect("block index");
+        coin_db
+            .block_db
+            .indexes
+            .batch(batch, self.block_previous, &prev_index);
+        coin_db
+            .block_db
+            .indexes
+            .batch(batch, self.block_hash, &block_index);
+        coin_db.states.batch(batch, STATE_KEY, &new_state);
+        coin_db.undos.batch(batch, self.block_hash, &self.undo);
+        for (key, account) in self.accounts {
+            coin_db.accounts.batch(batch, key, &account);
+        }
+        for (id, htlc) in self.htlcs {
+            match htlc {
+                Some(htlc) => coin_db.htlcs.batch(batch, id, &htlc),
+                None => coin_db.htlcs.batch_remove(batch, id),
+            }
+        }
+        for (id, multisig) in self.multisigs {
+            match multisig {
+                Some(multisig) => coin_db.multisigs.batch(batch, id, &multisig),
+                None => coin_db.multisigs.batch_remove(batch, id),
+            }
+        }
+
+        self.write_batch.commit().unwrap();
+        self.coin_db
+            .wallet_db
+            .set_anchor(new_state.rolling_checkpoint);
+        self.coin_db.state.store(Arc::new(new_state));
+    }
+}
+
+// without the undo bookkeeping of get_htlc and get_multisig
+impl Contracts for Update {
+    fn htlc(&self, id: HashTimeLockContractId) -> Option<HTLC> {
+        match self.htlcs.get(&id) {
+            Some(htlc) => htlc.clone(),
+            None => self.coin_db.htlcs.get(id),
+        }
+    }
+
+    fn multisig(&self, id: MultiSignatureLockContractId) -> Option<Multisig> {
+        match self.multisigs.get(&id) {
+            Some(multisig) => multisig.clone(),
+            None => self.coin_db.multisigs.get(id),
+        }
     }
 }
 
@@ -566,6 +994,16 @@ impl CoinTx for Update {
     }
 }
 
+impl Contracts for CoinDB {
+    fn htlc(&self, id: HashTimeLockContractId) -> Option<HTLC> {
+        self.htlcs.get(id)
+    }
+
+    fn multisig(&self, id: MultiSignatureLockContractId) -> Option<Multisig> {
+        self.multisigs.get(id)
+    }
+}
+
 #[derive(Deserialize, Serialize)]
 pub struct Check {
     result: bool,
@@ -15,8 +15,9 @@
  * along with this program. If not, see <https://www.gnu.org/licenses/>.
  */
 
+use crate::banlist::DEFAULT_BAN_TIME;
 use crate::endpoint::Endpoint;
-use crate::node::Node;
+use crate::node::{NETWORK_TIMEOUT, Node};
 use crate::packet::{
     BlockAnnounce, INVENTORY_SEND_MAX, INVENTORY_SEND_TIMEOUT, Inventory, PACKET_HEADER_SIZE_BYTES,
     Packet, PacketKind,
@@ -31,16 +32,14 @@ use blacknet_serialization::format::to_bytes;
 use blacknet_time::{Milliseconds, Seconds, SystemClock};
 use bytemuck::NoUninit;
 use core::cmp::min;
+use core::mem::take;
 use std::sync::{Arc, Mutex, MutexGuard, RwLock, atomic::*};
-use tokio::io::{AsyncReadExt, AsyncWriteExt, BufReader, BufWriter};
-use tokio::net::TcpStream;
-use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
-use tokio::runtime::Runtime;
-use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader, BufWriter, split};
+use tokio::runtime::Handle;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
 use tokio::task::JoinHandle;
-use tokio::time::sleep;
+use tokio::time::{sleep, timeout};
 
-#[expect(dead_code)]
 pub struct Connection {
     logger: Logger,
     handles: RwLock<Vec<JoinHandle<()>>>,
@@ -55,7 +54,7 @@ pub struct Connection {
     closed: AtomicBool,
     dos_score: AtomicU8,
     send_channel_size: AtomicUsize,
-    send_channel: UnboundedSender<(PacketKind, Vec<u8>)>,
+    send_channel: UnboundedSender<Option<(PacketKind, Vec<u8>)>>,
     inventory_to_send: Mutex<Vec<Hash>>,
     connected_at: Milliseconds,
 
@@ -76,14 +75,62 @@ pub struct Connection {
     fee_filter: Atomic<Amount>,
 }
 
+// None asks sender to shut down after queued packets
+type SendChannel = UnboundedReceiver<Option<(PacketKind, Vec<u8>)>>;
+
 impl Connection {
-    #[expect(unused)]
-    pub fn launch(self: Arc<Self>, tcp_stream: TcpStream, runtime: &Runtime) {
+    pub fn new(
+        id: u64,
+        node: Arc<Node>,
+        logger: Logger,
+        remote_endpoint: Endpoint,
+        local_endpoint: Endpoint,
+        state: State,
+    ) -> (Arc<Self>, SendChannel) {
+        let (send_channel, recv_channel) = unbounded_channel();
+        let now = SystemClock::millis();
+        let connection = Arc::new(Self {
+            logger,
+            handles: RwLock::new(Vec::new()),
+            node,
+            remote_endpoint,
+            local_endpoint,
+            state: Atomic::new(state),
+            total_bytes_read: AtomicU64::new(0),
+            total_bytes_written: AtomicU64::new(0),
+            closed: AtomicBool::new(false),
+            dos_score: AtomicU8::new(0),
+            send_channel_size: AtomicUsize::new(0),
+            send_channel,
+            inventory_to_send: Mutex::new(Vec::new()),
+            connected_at: now,
+            last_packet_time: Atomic::new(now),
+            last_block: ArcSwap::from_pointee(BlockAnnounce::default()),
+            last_block_time: Atomic::new(Milliseconds::ZERO),
+            last_tx_time: Atomic::new(Milliseconds::ZERO),
+            last_ping_time: Atomic::new(Milliseconds::ZERO),
+            last_inv_sent_time: Atomic::new(now),
+            time_offset: Atomic::new(Seconds::ZERO),
+            ping: Atomic::new(Milliseconds::ZERO),
+            ping_request: ArcSwapOption::empty(),
+            requested_difficulty: Atomic::new(UInt256::ZERO),
+            id,
+            version: AtomicU32::new(0),
+            agent: ArcSwap::from_pointee(String::new()),
+            fee_filter: Atomic::new(Amount::ZERO),
+        });
+        (connection, recv_channel)
+    }
+
+    pub fn launch<S>(self: Arc<Self>, stream: S, recv_channel: SendChannel, runtime: &Handle)
+    where
+        S: AsyncRead + AsyncWrite + Send + 'static,
+    {
         let mut handles = self.handles.write().unwrap();
-        let (tcp_read, tcp_write) = tcp_stream.into_split();
+        let (read, write) = split(stream);
         handles.push(runtime.spawn(self.clone().pusher()));
-        handles.push(runtime.spawn(self.clone().receiver(tcp_read)));
-        handles.push(runtime.spawn(self.clone().sender(todo!(), tcp_write)));
+        handles.push(runtime.spawn(self.clone().receiver(read)));
+        handles.push(runtime.spawn(self.clone().sender(recv_channel, write)));
     }
 
     pub async fn join(&self) {
@@ -146,6 +193,9 @@ impl Connection {
     }
 
     pub fn send_packet<T: Packet>(&self, packet: &T) {
+        if self.is_closed() {
+            return;
+        }
         let bytes = match to_bytes(&packet) {
             Ok(bytes) => bytes,
             Err(err) => {
@@ -160,7 +210,7 @@ impl Connection {
             + bytes.len()
             <= self.node().max_packet_size() as usize * 10
         {
-            self.send_channel.send((T::kind(), bytes)).unwrap();
+            let _ = self.send_channel.send(Some((T::kind(), bytes)));
         } else {
             info!(self.logger, "Disconnecting on send queue overflow");
             self.close();
@@ -172,29 +222,57 @@ impl Connection {
         self.fee_filter() <= fee
     }
 
-    #[expect(unreachable_code)]
+    /**
+     * Pending inventory is flushed, and the sender task finishes teardown,
+     * so that close can be called while holding a lock on node connections.
+     */
     pub fn close(&self) {
         if !self.closed.fetch_or(true, Ordering::AcqRel) {
-            todo!("close socket");
-
-            if let Ok(mut connections) = self.node().connections().write() {
-                if let Some(index) = connections
-                    .iter()
-                    .position(|connection| connection.id() == self.id())
-                {
-                    connections.swap_remove(index);
-                } else {
-                    error!(self.logger(), "Close can't find connection");
-                }
+            if self.is_established() {
+                self.flush_inventory();
             }
+            let _ = self.send_channel.send(None);
+        }
+    }
 
-            if self.is_established() {
-                self.node().block_fetcher().disconnected(self);
+    fn flush_inventory(&self) {
+        // close may be reached from send_inventory_impl
+        let Ok(mut inventory_to_send) = self.inventory_to_send.try_lock() else {
+            return;
+        };
+        if inventory_to_send.is_empty() {
+            return;
+        }
+        match to_bytes(&Inventory::new(take(&mut *inventory_to_send))) {
+            Ok(bytes) => {
+                let _ = self.send_channel.send(Some((Inventory::kind(), bytes)));
             }
+            Err(err) => error!(self.logger, "Serialization error: {err}"),
+        }
+    }
 
-            let handles = self.handles.read().unwrap();
-            handles.iter().for_each(JoinHandle::abort);
+    fn teardown(&self) {
+        {
+            let mut connections = self.node.connections().write().unwrap();
+            if let Some(index) = connections
+                .iter()
+                .position(|connection| connection.id() == self.id())
+            {
+                connections.swap_remove(index);
+            } else {
+                error!(self.logger, "Close can't find connection");
+            }
+        }
+
+        if self.is_established() {
+            self.node.block_fetcher().disconnected(self);
+        }
+        if self.state().is_outgoing() {
+            self.node.peer_table().discontacted(self.remote_endpoint);
         }
+
+        let handles = self.handles.read().unwrap();
+        handles.iter().for_each(JoinHandle::abort);
     }
 
     pub fn is_closed(&self) -> bool {
@@ -204,7 +282,16 @@ impl Connection {
     pub fn dos(&self, reason: &str) {
         let score = self.dos_score.fetch_add(1, Ordering::AcqRel) + 1;
         if score == 100 {
-            self.close();
+            if self.remote_endpoint.is_local() {
+                self.close();
+            } else {
+                self.node.ban(
+                    self.remote_endpoint,
+                    false,
+                    DEFAULT_BAN_TIME,
+                    reason.to_owned(),
+                );
+            }
         }
         info!(self.logger, "{reason} DoS {score}");
     }
@@ -243,6 +330,15 @@ impl Connection {
             .store(last_packet_time, Ordering::Release);
     }
 
+    pub fn last_block_time(&self) -> Milliseconds {
+        self.last_block_time.load(Ordering::Acquire)
+    }
+
+    pub fn set_last_block_time(&self, last_block_time: Milliseconds) {
+        self.last_block_time
+            .store(last_block_time, Ordering::Release);
+    }
+
     pub fn last_tx_time(&self) -> Milliseconds {
         self.last_tx_time.load(Ordering::Acquire)
     }
@@ -379,68 +475,102 @@ impl Connection {
         }
     }
 
-    async fn receiver(self: Arc<Self>, tcp_read: OwnedReadHalf) {
-        let mut buf_reader = BufReader::new(tcp_read);
+    async fn receiver<R: AsyncRead + Unpin>(self: Arc<Self>, read: R) {
+        let mut buf_reader = BufReader::new(read);
         loop {
-            let size = buf_reader.read_u32().await.unwrap();
-            let max = self.node.max_packet_size();
-            if size > max {
-                if self.is_established() {
-                    info!(
-                        self.logger,
-                        "Too long packet {size} max {max} Disconnecting"
-                    );
-                }
-                self.close();
-                break;
-            }
-            let kind: PacketKind = match buf_reader.read_u32().await.unwrap().try_into() {
-                Ok(kind) => kind,
-                Err(msg) => {
-                    info!(self.logger, "{msg} Disconnecting");
-                    self.close();
+            match self.receive_packet(&mut buf_reader).await {
+                Ok(true) => (),
+                Ok(false) => break,
+                Err(err) => {
+                    debug!(self.logger, "{err}");
                     break;
                 }
-            };
-            if (self.is_established() && kind.is_handshake())
-                || (!self.is_established() && !kind.is_handshake())
-            {
-                self.close();
-                break;
             }
-            let mut bytes = vec![0; size as usize];
-            buf_reader.read_exact(&mut bytes).await.unwrap();
-            debug!(self.logger, "Received {kind:?}");
-            if !kind.handle(&bytes, &self) {
-                break;
+        }
+        self.close();
+    }
+
+    async fn receive_packet<R: AsyncRead + Unpin>(
+        self: &Arc<Self>,
+        buf_reader: &mut BufReader<R>,
+    ) -> Result<bool, std::io::Error> {
+        let size = buf_reader.read_u32().await?;
+        let max = self.node.max_packet_size();
+        if size > max {
+            if self.is_established() {
+                info!(
+                    self.logger,
+                    "Too long packet {size} max {max} Disconnecting"
+                );
             }
-            self.set_last_packet_time(SystemClock::millis());
-            self.total_bytes_read
-                .fetch_add(4 + size as u64, Ordering::Relaxed);
+            return Ok(false);
         }
+        let kind: PacketKind = match buf_reader.read_u32().await?.try_into() {
+            Ok(kind) => kind,
+            Err(msg) => {
+                info!(self.logger, "{msg} Disconnecting");
+                return Ok(false);
+            }
+        };
+        if (self.is_established() && kind.is_handshake())
+            || (!self.is_established() && !kind.is_handshake())
+        {
+            return Ok(false);
+        }
+        let mut bytes = vec![0; size as usize];
+        buf_reader.read_exact(&mut bytes).await?;
+        debug!(self.logger, "Received {kind:?}");
+        if !kind.handle(&bytes, self) {
+            return Ok(false);
+        }
+        self.set_last_packet_time(SystemClock::millis());
+        self.total_bytes_read
+            .fetch_add(4 + size as u64, Ordering::Relaxed);
+        Ok(true)
     }
 
-    async fn sender(
+    async fn sender<W: AsyncWrite + Unpin>(
         self: Arc<Self>,
-        mut recv_channel: UnboundedReceiver<(PacketKind, Vec<u8>)>,
-        tcp_write: OwnedWriteHalf,
+        mut recv_channel: SendChannel,
+        write: W,
     ) {
-        let mut buf_writer = BufWriter::new(tcp_write);
-        loop {
-            let (kind, bytes) = recv_channel.recv().await.unwrap();
+        let mut buf_writer = BufWriter::new(write);
+        let duration = NETWORK_TIMEOUT.try_into().unwrap();
+        while let Some(Some((kind, bytes))) = recv_channel.recv().await {
             debug!(self.logger, "Sending {:?}", kind);
-            buf_writer
-                .write_u32(bytes.len() as u32 + PACKET_HEADER_SIZE_BYTES)
-                .await
-                .unwrap();
-            buf_writer.write_u32(kind as u32).await.unwrap();
-            buf_writer.write_all(&bytes).await.unwrap();
-            buf_writer.flush().await.unwrap();
+            match timeout(duration, Self::write_packet(&mut buf_writer, kind, &bytes)).await {
+                Ok(Ok(())) => (),
+                Ok(Err(err)) => {
+                    debug!(self.logger, "{err}");
+                    self.close();
+                    break;
+                }
+                Err(_) => {
+                    info!(self.logger, "Send timeout Disconnecting");
+                    self.close();
+                    break;
+                }
+            }
             self.send_channel_size
                 .fetch_sub(bytes.len(), Ordering::AcqRel);
             self.total_bytes_written
                 .fetch_add(8 + bytes.len() as u64, Ordering::Relaxed);
         }
+        let _ = timeout(duration, buf_writer.shutdown()).await;
+        self.teardown();
+    }
+
+    async fn write_packet<W: AsyncWrite + Unpin>(
+        buf_writer: &mut BufWriter<W>,
+        kind: PacketKind,
+        bytes: &[u8],
+    ) -> Result<(), std::io::Error> {
+        buf_writer
+            .write_u32(bytes.len() as u32 + PACKET_HEADER_SIZE_BYTES)
+            .await?;
+        buf_writer.write_u32(kind as u32).await?;
+        buf_writer.write_all(bytes).await?;
+        buf_writer.flush().await
     }
 }
 
@@ -16,12 +16,12 @@
  */
 
 use crate::fjall::Fjall;
-use blacknet_serialization::format::from_bytes;
+use blacknet_serialization::format::{from_bytes, to_bytes};
 use core::fmt::Debug;
 use core::marker::PhantomData;
 use core::ops::Deref;
 use fjall::{Keyspace, OwnedWriteBatch as WriteBatch, Result};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 pub struct DBView<K: AsRef<[u8]>, V: for<'de> Deserialize<'de>> {
     keyspace: Keyspace,
@@ -85,11 +85,38 @@ impl<K: AsRef<[u8]>, V: for<'de> Deserialize<'de>> DBView<K, V> {
         })
     }
 
+    /**
+     * In order of keys, reversible to get the last ones first
+     */
+    pub fn prefix(&self, prefix: &[u8]) -> impl DoubleEndedIterator<Item = (K, V)>
+    where
+        K: for<'a> TryFrom<&'a [u8], Error: Debug>,
+    {
+        self.keyspace.prefix(prefix).map(|item| {
+            let (key, value) = item.into_inner().unwrap();
+            (
+                key.as_ref().try_into().unwrap(),
+                from_bytes::<V>(&value, false).unwrap(),
+            )
+        })
+    }
+
     pub fn count(&self) -> usize {
         self.keyspace.len().unwrap()
     }
 
+    pub fn batch(&self, batch: &mut WriteBatch, key: K, value: &V)
+    where
+        V: Serialize,
+    {
+        batch.insert(&self.keyspace, key.as_ref(), to_bytes(value).unwrap())
+    }
+
     pub fn batch_bytes(&self, batch: &mut WriteBatch, key: K, bytes: &[u8]) {
         batch.insert(&self.keyspace, key.as_ref(), bytes)
     }
+
+    pub fn batch_remove(&self, batch: &mut WriteBatch, key: K) {
+        batch.remove(&self.keyspace, key.as_ref())
+    }
 }
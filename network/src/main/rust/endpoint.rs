@@ -15,7 +15,7 @@
  * along with this program. If not, see <https://www.gnu.org/licenses/>.
  */
 
-use core::net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
 use core::str::FromStr;
 use data_encoding::Encoding;
 use data_encoding_macro::new_encoding;
@@ -41,6 +41,61 @@ impl Endpoint {
         parse_ipv4(string, port))))
     }
 
+    pub const fn port(self) -> u16 {
+        match self {
+            Endpoint::IPv4 { port, .. } => port,
+            Endpoint::IPv6 { port, .. } => port,
+            Endpoint::TORv2 { port, .. } => port,
+            Endpoint::TORv3 { port, .. } => port,
+            Endpoint::I2P { port, .. } => port,
+        }
+    }
+
+    /**
+     * Address without port, as remote port of incoming connection is usually ephemeral
+     */
+    pub const fn host(self) -> Self {
+        match self {
+            Endpoint::IPv4 { port: _, address } => Endpoint::IPv4 { port: 0, address },
+            Endpoint::IPv6 { port: _, address } => Endpoint::IPv6 { port: 0, address },
+            Endpoint::TORv2 { port: _, address } => Endpoint::TORv2 { port: 0, address },
+            Endpoint::TORv3 { port: _, address } => Endpoint::TORv3 { port: 0, address },
+            Endpoint::I2P { port: _, address } => Endpoint::I2P { port: 0, address },
+        }
+    }
+
+    pub const fn with_port(self, port: u16) -> Self {
+        match self {
+            Endpoint::IPv4 { port: _, address } => Endpoint::IPv4 { port, address },
+            Endpoint::IPv6 { port: _, address } => Endpoint::IPv6 { port, address },
+            Endpoint::TORv2 { port: _, address } => Endpoint::TORv2 { port, address },
+            Endpoint::TORv3 { port: _, address } => Endpoint::TORv3 { port, address },
+            Endpoint::I2P { port: _, address } => Endpoint::I2P { port, address },
+        }
+    }
+
+    /**
+     * Network prefix that is likely to be under control of a single operator
+     */
+    pub const fn subnet(self) -> Option<Self> {
+        match self {
+            Endpoint::IPv4 { port: _, address } => Some(Endpoint::IPv4 {
+                port: 0,
+                address: [address[0], address[1], 0, 0],
+            }),
+            Endpoint::IPv6 { port: _, address } => Some(Endpoint::IPv6 {
+                port: 0,
+                address: [
+                    address[0], address[1], address[2], address[3], 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                    0, 0,
+                ],
+            }),
+            Endpoint::TORv2 { .. } => None,
+            Endpoint::TORv3 { .. } => None,
+            Endpoint::I2P { .. } => None,
+        }
+    }
+
     pub const fn is_permissionless(self) -> bool {
         match self {
             Endpoint::IPv4 { .. } => false,
@@ -110,6 +165,22 @@ impl Endpoint {
     }
 }
 
+impl From<SocketAddr> for Endpoint {
+    fn from(addr: SocketAddr) -> Self {
+        // IPv4-mapped addresses of dual-stack socket
+        match addr.ip().to_canonical() {
+            IpAddr::V4(ip) => Endpoint::IPv4 {
+                port: addr.port(),
+                address: ip.octets(),
+            },
+            IpAddr::V6(ip) => Endpoint::IPv6 {
+                port: addr.port(),
+                address: ip.octets(),
+            },
+        }
+    }
+}
+
 fn parse_ipv4(string: &str, port: u16) -> Option<Endpoint> {
     if let Ok(addr) = Ipv4Addr::from_str(string) {
         Some(Endpoint::IPv4 {
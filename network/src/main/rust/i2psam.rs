@@ -209,16 +209,55 @@ impl Session {
         }
     }
 
-    pub async fn accept(&self) -> Result<Connection, Error> {
+    pub fn streams(&self) -> Streams {
+        Streams {
+            logger: self.logger.clone(),
+            id: self.id.clone(),
+            local_endpoint: self.local_endpoint,
+            sam_endpoint: self.sam_endpoint,
+        }
+    }
+
+    pub const fn endpoint(&self) -> Endpoint {
+        self.local_endpoint
+    }
+}
+
+#[derive(Clone)]
+pub struct Streams {
+    logger: Logger,
+    id: String,
+    local_endpoint: Endpoint,
+    sam_endpoint: Endpoint,
+}
+
+impl Streams {
+    pub async fn accept(&self) -> Result<(BufStream<TcpStream>, Endpoint), Error> {
         let mut connection = Connection::new(self.logger.clone(), self.sam_endpoint).await?;
         let request = format!("STREAM ACCEPT ID={}\n", self.id);
         connection.request(&request).await?;
+        // destination of the remote peer followed by options
         let message = connection.read().await?;
-        if message.starts_with("STREAM STATUS") {
-            let answer = Answer::new(message);
-            answer.ok()?;
-        }
-        Ok(connection)
+        let destination = match message.split([' ', '\n']).next() {
+            Some(destination) if !destination.is_empty() => destination,
+            _ => return Err(Error::Message("accept returned no destination".to_owned())),
+        };
+        let remote_endpoint = Endpoint::I2P {
+            port: self.local_endpoint.port(),
+            address: Answer::hash(destination)?,
+        };
+        Ok((connection.stream, remote_endpoint))
+    }
+
+    pub async fn connect(&self, endpoint: Endpoint) -> Result<BufStream<TcpStream>, Error> {
+        let mut connection = Connection::new(self.logger.clone(), self.sam_endpoint).await?;
+        let destination = connection.lookup(&endpoint.to_host()).await?;
+        let request = format!(
+            "STREAM CONNECT ID={} DESTINATION={} SILENT=false\n",
+            self.id, destination
+        );
+        connection.request(&request).await?;
+        Ok(connection.stream)
     }
 
     pub const fn endpoint(&self) -> Endpoint {
@@ -15,8 +15,11 @@
  * along with this program. If not, see <https://www.gnu.org/licenses/>.
  */
 
+pub mod addressindex;
+pub mod banlist;
 pub mod blockdb;
 pub mod blockfetcher;
+pub mod bootstrap;
 pub mod coindb;
 pub mod connection;
 pub mod dbview;
@@ -26,6 +29,7 @@ pub mod genesis;
 pub mod i2psam;
 pub mod natpmp;
 pub mod node;
+pub mod notifier;
 pub mod packet;
 pub mod peertable;
 pub mod rollinghashset;
@@ -35,5 +39,7 @@ pub mod socks5;
 pub mod staker;
 pub mod torcontroller;
 pub mod txfetcher;
+pub mod txindex;
 pub mod txpool;
 pub mod undoblock;
+pub mod walletsync;
@@ -15,52 +15,74 @@
  * along with this program. If not, see <https://www.gnu.org/licenses/>.
  */
 
+use crate::banlist::BanList;
 use crate::blockdb::BlockDB;
 use crate::blockfetcher::BlockFetcher;
+use crate::bootstrap;
 use crate::coindb::CoinDB;
 use crate::connection::{Connection, State};
 use crate::endpoint::Endpoint;
 use crate::fjall::Fjall;
-use crate::packet::UnfilteredInvList;
+use crate::notifier::Notifier;
+use crate::packet::{BlockAnnounce, UnfilteredInvList, send_handshake};
 use crate::peertable::PeerTable;
 use crate::router::Router;
 use crate::settings::Settings;
+use crate::socks5::socks5;
 use crate::staker::Staker;
 use crate::txfetcher::TxFetcher;
 use crate::txpool::TxPool;
+use crate::walletsync;
 use blacknet_compat::{Mode, XDGDirectories, getuid, uname};
+use blacknet_crypto::bigint::UInt256;
 use blacknet_crypto::random::{Distribution, FAST_RNG, FastRNG, UniformIntDistribution};
 use blacknet_io::Write;
 use blacknet_io::file::replace;
+use blacknet_kernel::amount::Amount;
 use blacknet_kernel::blake2b::Hash;
+use blacknet_kernel::ed25519::PublicKey;
 use blacknet_kernel::error::Error;
 use blacknet_kernel::proofofstake::{
     BLOCK_RESERVED_SIZE, DEFAULT_MAX_BLOCK_SIZE, guess_initial_synchronization, time_slot,
 };
-use blacknet_log::{LogManager, Logger, error, info, warn};
-use blacknet_serialization::format::to_write;
+use blacknet_kernel::transaction::Transaction;
+use blacknet_log::{LogManager, Logger, debug, error, info, warn};
+use blacknet_serialization::format::{from_read, to_write};
 use blacknet_time::{Milliseconds, Seconds, SystemClock};
-use blacknet_wallet::walletdb::WalletDB;
+use blacknet_wallet::walletdb::{Error as WalletError, WalletDB};
 use core::error::Error as StdError;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
-use std::path::PathBuf;
-use std::sync::atomic::AtomicU64;
-use std::sync::{Arc, RwLock};
-use tokio::runtime::Runtime;
-use tokio::time::{Duration, sleep};
+use std::fs::File;
+use std::io::{BufReader, ErrorKind, Read};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock, Weak};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpStream;
+use tokio::runtime::{Handle, Runtime};
+use tokio::time::{Duration, sleep, timeout};
 
 pub const NETWORK_TIMEOUT: Milliseconds = Milliseconds::with_seconds(90);
 pub const PROTOCOL_VERSION: u32 = 15;
 pub const MIN_PROTOCOL_VERSION: u32 = 12;
+const MAX_INCOMING_PER_SUBNET: usize = 4;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const PROXY_CONNECT_TIMEOUT: Duration = Duration::from_secs(60);
+const CONNECTOR_INTERVAL: Duration = Duration::from_secs(1);
+const CONNECTOR_IDLE_INTERVAL: Duration = Duration::from_secs(60);
+const PROBER_INTERVAL: Duration = Duration::from_secs(4 * 60);
 
-#[expect(dead_code)]
 pub struct Node {
     logger: Logger,
+    connection_logger: Logger,
+    runtime: Handle,
     settings: Arc<Settings>,
     state_dir: PathBuf,
     next_peer_id: AtomicU64,
-    connections: RwLock<Vec<Connection>>,
+    connections: RwLock<Vec<Arc<Connection>>>,
+    anchors: Mutex<Vec<Endpoint>>,
+    ban_list: Arc<BanList>,
     peer_table: Arc<PeerTable>,
     router: Arc<Router>,
     fjall: Arc<Fjall>,
@@ -69,7 +91,8 @@ pub struct Node {
     block_fetcher: BlockFetcher,
     tx_pool: Arc<RwLock<TxPool>>,
     tx_fetcher: Arc<TxFetcher>,
-    wallet_db: WalletDB,
+    wallet_db: Arc<WalletDB>,
+    notifier: Arc<Notifier>,
     staker: Staker,
     agent_string: String,
     prober_agent_string: String,
@@ -85,6 +108,7 @@ impl Node {
         dirs: &XDGDirectories,
         log_manager: &LogManager,
         runtime: &Runtime,
+        settings: Settings,
     ) -> Result<Arc<Self>, Box<dyn StdError>> {
         let (os_name, os_version, os_machine) = uname();
         let (agent_name, agent_version) = (mode.agent_name(), env!("CARGO_PKG_VERSION"));
@@ -104,32 +128,56 @@ impl Node {
             warn!(logger, "Running as root");
         }
 
-        let settings = Arc::new(Settings::default(&mode));
-        let peer_table = PeerTable::new(&mode, dirs, log_manager, settings.clone())?;
+        let anchors = Self::load(&logger, dirs.state());
+        if !anchors.is_empty() {
+            info!(logger, "Loaded {} anchor peers", anchors.len());
+        }
+
+        let settings = Arc::new(settings);
+        let ban_list = BanList::new(dirs, log_manager, settings.clone())?;
+        let peer_table =
+            PeerTable::new(&mode, dirs, log_manager, settings.clone(), ban_list.clone())?;
         let fjall = Fjall::open(dirs, &settings)?;
-        let block_db = BlockDB::new(&mode, dirs, fjall.clone(), log_manager)?;
-        let coin_db = CoinDB::new(&mode, &fjall, block_db.clone(), log_manager)?;
+        let notifier = Notifier::new();
+        let wallet_db = Arc::new(WalletDB::new(&mode, dirs, log_manager)?);
+        let block_db = BlockDB::new(&mode, dirs, fjall.clone(), log_manager, notifier.clone())?;
+        let coin_db = CoinDB::new(
+            &mode,
+            &fjall,
+            block_db.clone(),
+            log_manager,
+            &settings,
+            wallet_db.clone(),
+        )?;
         let tx_pool = Arc::new(RwLock::new(TxPool::new(
             log_manager,
             settings.clone(),
             coin_db.clone(),
+            notifier.clone(),
+            wallet_db.clone(),
         )?));
+        tx_pool.write().unwrap().load(dirs.state());
         let node = Arc::new(Self {
             logger,
+            connection_logger: log_manager.logger("Connection")?,
+            runtime: runtime.handle().clone(),
             settings: settings.clone(),
             state_dir: dirs.state().to_owned(),
             next_peer_id: AtomicU64::new(1),
             connections: RwLock::new(Vec::new()),
+            anchors: Mutex::new(anchors),
+            ban_list,
             peer_table: peer_table.clone(),
-            router: Router::new(&mode, dirs, log_manager, runtime, &settings, peer_table)?,
+            router: Router::new(&mode, dirs, log_manager, &settings, peer_table)?,
             fjall,
             block_db,
             coin_db: coin_db.clone(),
-            block_fetcher: BlockFetcher::new(coin_db, &settings),
+            block_fetcher: BlockFetcher::new(log_manager, coin_db.clone(), &settings)?,
             tx_pool: tx_pool.clone(),
             tx_fetcher: TxFetcher::new(runtime, Arc::downgrade(&tx_pool)),
-            wallet_db: WalletDB::new(&mode, dirs, log_manager)?,
-            staker: Staker::new(log_manager)?,
+            wallet_db,
+            notifier,
+            staker: Staker::new(log_manager, coin_db)?,
             agent_string: format!("/{agent_name}:{agent_version}/"),
             prober_agent_string: format!("/{agent_name}-prober:{agent_version}/"),
             agent_name: agent_name.to_owned(),
@@ -138,8 +186,25 @@ impl Node {
             mode,
         });
 
+        node.router.launch(runtime, &Arc::downgrade(&node));
+        runtime.spawn(BlockFetcher::implementation(Arc::downgrade(&node)));
+        runtime.spawn(Staker::implementation(Arc::downgrade(&node)));
+        runtime.spawn(Self::connector(Arc::downgrade(&node)));
+        runtime.spawn(Self::prober(Arc::downgrade(&node)));
         runtime.spawn(node.clone().rotator());
 
+        let bootstrap = settings
+            .bootstrap
+            .clone()
+            .unwrap_or_else(|| dirs.data().join(bootstrap::FILE_NAME));
+        if bootstrap.is_file() {
+            let logger = log_manager.logger("Bootstrap")?;
+            let node = Arc::downgrade(&node);
+            std::thread::Builder::new()
+                .name("bootstrap".to_owned())
+                .spawn(move || bootstrap::import(&logger, &node, &bootstrap))?;
+        }
+
         Ok(node)
     }
 
@@ -170,7 +235,9 @@ impl Node {
 
     pub fn is_online(&self) -> bool {
         let connections = self.connections.read().unwrap();
-        connections.iter().any(Connection::is_established)
+        connections
+            .iter()
+            .any(|connection| connection.is_established())
     }
 
     pub fn outgoing(&self) -> usize {
@@ -189,7 +256,7 @@ impl Node {
             .count()
     }
 
-    pub const fn connections(&self) -> &RwLock<Vec<Connection>> {
+    pub const fn connections(&self) -> &RwLock<Vec<Arc<Connection>>> {
         &self.connections
     }
 
@@ -257,6 +324,33 @@ impl Node {
         &self.coin_db
     }
 
+    pub fn ban_list(&self) -> &BanList {
+        &self.ban_list
+    }
+
+    /**
+     * Also closes affected connections
+     */
+    pub fn ban(
+        &self,
+        endpoint: Endpoint,
+        subnet: bool,
+        duration: Milliseconds,
+        reason: String,
+    ) -> bool {
+        if !self.ban_list.ban(endpoint, subnet, duration, reason) {
+            return false;
+        }
+        let connections = self.connections.read().unwrap();
+        for connection in connections.iter() {
+            let remote_endpoint = connection.remote_endpoint();
+            if !remote_endpoint.is_local() && self.ban_list.is_banned(remote_endpoint) {
+                connection.close();
+            }
+        }
+        true
+    }
+
     pub fn peer_table(&self) -> &PeerTable {
         &self.peer_table
     }
@@ -269,10 +363,52 @@ impl Node {
         &self.tx_fetcher
     }
 
-    pub const fn wallet_db(&self) -> &WalletDB {
+    pub fn wallet_db(&self) -> &WalletDB {
         &self.wallet_db
     }
 
+    pub fn create_wallet(&self, public_key: PublicKey) -> Result<(), WalletError> {
+        let _processing = self.block_db.lock();
+        let state = self.coin_db.state();
+        self.wallet_db
+            .create(&self.mode, public_key, (state.block_hash(), state.height()))?;
+        self.tx_pool.write().unwrap().wallet_added();
+        Ok(())
+    }
+
+    /**
+     * Confirmed balance of wallet and the one that counts transactions of TxPool
+     */
+    pub fn wallet_balance(&self, public_key: PublicKey) -> Result<(Amount, Amount), WalletError> {
+        if !self.wallet_db.contains(public_key) {
+            return Err(WalletError::UnknownWallet);
+        }
+        let balance = self
+            .coin_db
+            .account(public_key)
+            .map_or(Amount::ZERO, |account| account.balance());
+        let unconfirmed_balance = self.tx_pool.read().unwrap().balance(public_key);
+        Ok((balance, unconfirmed_balance))
+    }
+
+    /**
+     * Rebuilds the wallet from blocks starting at `height`, that is expected to precede its first transaction
+     */
+    pub fn rescan_wallet(&self, public_key: PublicKey, height: u32) -> Result<(), WalletError> {
+        walletsync::rescan(
+            &self.logger,
+            &self.block_db,
+            &self.coin_db,
+            &self.wallet_db,
+            public_key,
+            height,
+        )
+    }
+
+    pub fn notifier(&self) -> &Notifier {
+        &self.notifier
+    }
+
     pub const fn staker(&self) -> &Staker {
         &self.staker
     }
@@ -319,6 +455,356 @@ impl Node {
         }
     }
 
+    pub fn announce_block(
+        &self,
+        hash: Hash,
+        cumulative_difficulty: UInt256,
+        source: Option<u64>,
+    ) -> usize {
+        let block_announce = BlockAnnounce::new(hash, cumulative_difficulty);
+        let mut n = 0;
+        let connections = self.connections.read().unwrap();
+        for connection in connections.iter() {
+            if Some(connection.id()) != source
+                && connection.is_established()
+                && connection.last_block().load().cumulative_difficulty() < cumulative_difficulty
+            {
+                connection.send_packet(&block_announce);
+                n += 1;
+            }
+        }
+        n
+    }
+
+    pub(crate) fn accept<S>(
+        self: &Arc<Self>,
+        stream: S,
+        remote_endpoint: Endpoint,
+        local_endpoint: Endpoint,
+    ) where
+        S: AsyncRead + AsyncWrite + Send + 'static,
+    {
+        if !remote_endpoint.is_local() && self.ban_list.is_banned(remote_endpoint) {
+            debug!(
+                self.logger,
+                "Rejecting banned {}",
+                remote_endpoint.to_log(self.settings.log_endpoint)
+            );
+            return;
+        }
+        {
+            // Tor connections come from loopback
+            let subnet = if remote_endpoint.is_local() {
+                None
+            } else {
+                remote_endpoint.subnet()
+            };
+            let connections = self.connections.read().unwrap();
+            let mut incoming = 0;
+            let mut same_subnet = 0;
+            for connection in connections.iter() {
+                if connection.state().is_incoming() {
+                    incoming += 1;
+                    if subnet.is_some() && connection.remote_endpoint().subnet() == subnet {
+                        same_subnet += 1;
+                    }
+                }
+            }
+            if incoming >= usize::from(self.settings.incoming_connections) {
+                debug!(
+                    self.logger,
+                    "Too many incoming connections, rejecting {}",
+                    remote_endpoint.to_log(self.settings.log_endpoint)
+                );
+                return;
+            }
+            if same_subnet >= MAX_INCOMING_PER_SUBNET {
+                debug!(
+                    self.logger,
+                    "Too many incoming connections from subnet of {}",
+                    remote_endpoint.to_log(self.settings.log_endpoint)
+                );
+                return;
+            }
+        }
+        self.add_connection(
+            stream,
+            remote_endpoint,
+            local_endpoint,
+            State::IncomingWaiting,
+        );
+    }
+
+    /**
+     * Remote port of incoming connection is usually ephemeral, so the peer is recorded at the default one
+     */
+    pub(crate) fn incoming_connected(&self, connection: &Connection) {
+        let endpoint = connection
+            .remote_endpoint()
+            .with_port(self.mode.default_p2p_port());
+        self.peer_table.connected(
+            endpoint,
+            connection.connected_at(),
+            connection.agent().load().to_string(),
+            false,
+        );
+    }
+
+    pub(crate) fn add_connection<S>(
+        self: &Arc<Self>,
+        stream: S,
+        remote_endpoint: Endpoint,
+        local_endpoint: Endpoint,
+        state: State,
+    ) -> Arc<Connection>
+    where
+        S: AsyncRead + AsyncWrite + Send + 'static,
+    {
+        let id = self.next_peer_id.fetch_add(1, Ordering::Relaxed);
+        let (connection, recv_channel) = Connection::new(
+            id,
+            self.clone(),
+            self.connection_logger.clone(),
+            remote_endpoint,
+            local_endpoint,
+            state,
+        );
+        self.connections.write().unwrap().push(connection.clone());
+        connection
+            .clone()
+            .launch(stream, recv_channel, &self.runtime);
+        connection
+    }
+
+    pub async fn connect(
+        self: &Arc<Self>,
+        endpoint: Endpoint,
+        prober: bool,
+    ) -> Result<Arc<Connection>, Box<dyn StdError + Send + Sync>> {
+        let state = if prober {
+            State::ProberWaiting
+        } else {
+            State::OutgoingWaiting
+        };
+        let connection = match endpoint {
+            Endpoint::IPv4 { .. } | Endpoint::IPv6 { .. } => {
+                let addr = endpoint.to_rust().expect("TCP/IP");
+                let stream = timeout(CONNECT_TIMEOUT, TcpStream::connect(addr)).await??;
+                let local_endpoint = stream.local_addr()?.into();
+                self.add_connection(stream, endpoint, local_endpoint, state)
+            }
+            Endpoint::TORv3 { .. } => {
+                let proxy =
+                    Endpoint::parse(&self.settings.tor_proxy_host, self.settings.tor_proxy_port)
+                        .ok_or("Can't parse settings.tor_proxy_host")?;
+                let stream = timeout(PROXY_CONNECT_TIMEOUT, socks5(proxy, endpoint)).await??;
+                let local_endpoint = stream.get_ref().local_addr()?.into();
+                self.add_connection(stream, endpoint, local_endpoint, state)
+            }
+            Endpoint::I2P { .. } => {
+                let (stream, local_endpoint) =
+                    timeout(PROXY_CONNECT_TIMEOUT, self.router.connect_i2p(endpoint)).await??;
+                self.add_connection(stream, endpoint, local_endpoint, state)
+            }
+            Endpoint::TORv2 { .. } => return Err("Obsolete TORv2 endpoint".into()),
+        };
+        if let Err(err) = send_handshake(&connection) {
+            connection.close();
+            return Err(err.into());
+        }
+        Ok(connection)
+    }
+
+    /**
+     * Manual connection, which unlike automatic ones is permitted to local and private endpoints
+     */
+    pub async fn add_peer(
+        self: &Arc<Self>,
+        endpoint: Endpoint,
+    ) -> Result<Arc<Connection>, Box<dyn StdError + Send + Sync>> {
+        if self.ban_list.is_banned(endpoint) {
+            return Err("Endpoint is banned".into());
+        }
+        let connected = {
+            let connections = self.connections.read().unwrap();
+            connections
+                .iter()
+                .any(|connection| connection.remote_endpoint() == endpoint)
+        };
+        if connected {
+            return Err("Already connected".into());
+        }
+        // peer table doesn't track these
+        let tracked = !endpoint.is_local() && !endpoint.is_private();
+        if tracked && !self.peer_table.try_contact(endpoint) {
+            return Err("Already connecting".into());
+        }
+        let result = self.connect(endpoint, false).await;
+        if result.is_err() && tracked {
+            self.peer_table.failed(endpoint, SystemClock::millis());
+            self.peer_table.discontacted(endpoint);
+        }
+        result
+    }
+
+    async fn dial(self: &Arc<Self>, endpoint: Endpoint, prober: bool) {
+        if let Err(err) = self.connect(endpoint, prober).await {
+            debug!(
+                self.logger,
+                "Can't connect to {}: {err}",
+                endpoint.to_log(self.settings.log_endpoint)
+            );
+            self.peer_table.failed(endpoint, SystemClock::millis());
+            self.peer_table.discontacted(endpoint);
+        }
+    }
+
+    // previous outgoing connections are tried first to resist eclipse attack
+    fn anchor(&self) -> Option<Endpoint> {
+        let mut anchors = self.anchors.lock().unwrap();
+        while let Some(endpoint) = anchors.pop() {
+            let connected = {
+                let connections = self.connections.read().unwrap();
+                connections
+                    .iter()
+                    .any(|connection| connection.remote_endpoint() == endpoint)
+            };
+            if !connected
+                && !self.ban_list.is_banned(endpoint)
+                && self.peer_table.try_contact(endpoint)
+            {
+                return Some(endpoint);
+            }
+        }
+        None
+    }
+
+    fn candidate(&self) -> Option<Endpoint> {
+        let connected = {
+            let connections = self.connections.read().unwrap();
+            connections
+                .iter()
+                .map(|connection| connection.remote_endpoint())
+                .collect::<HashSet<Endpoint>>()
+        };
+        let listening = self.router.listening().read().unwrap().clone();
+        let i2p = self.settings.i2p && self.router.is_i2p_ready();
+        self.peer_table.candidate(|endpoint, _| {
+            let reachable = match endpoint {
+                Endpoint::IPv4 { .. } => self.settings.ipv4,
+                Endpoint::IPv6 { .. } => self.settings.ipv6,
+                Endpoint::TORv2 { .. } => false,
+                Endpoint::TORv3 { .. } => self.settings.tor,
+                Endpoint::I2P { .. } => i2p,
+            };
+            reachable && !connected.contains(endpoint) && !listening.contains(endpoint)
+        })
+    }
+
+    async fn connector(weak: Weak<Self>) {
+        loop {
+            let interval = match weak.upgrade() {
+                Some(node) => node.fill_outgoing().await,
+                None => break,
+            };
+            sleep(interval).await;
+        }
+    }
+
+    async fn fill_outgoing(self: &Arc<Self>) -> Duration {
+        let now = SystemClock::millis();
+        let mut stale = Vec::new();
+        let mut outgoing = 0;
+        {
+            let connections = self.connections.read().unwrap();
+            for connection in connections.iter() {
+                let state = connection.state();
+                if !state.is_established() && now > connection.connected_at() + NETWORK_TIMEOUT {
+                    stale.push(connection.clone());
+                } else if matches!(state, State::OutgoingConnected | State::OutgoingWaiting) {
+                    outgoing += 1;
+                }
+            }
+        }
+        for connection in stale {
+            info!(connection.logger(), "Handshake timeout");
+            connection.close();
+        }
+
+        if outgoing >= usize::from(self.settings.outgoing_connections) {
+            return CONNECTOR_INTERVAL;
+        }
+        match self.anchor().or_else(|| self.candidate()) {
+            Some(endpoint) => {
+                self.dial(endpoint, false).await;
+                CONNECTOR_INTERVAL
+            }
+            None => CONNECTOR_IDLE_INTERVAL,
+        }
+    }
+
+    // keeping track of online peers
+    async fn prober(weak: Weak<Self>) {
+        loop {
+            sleep(PROBER_INTERVAL).await;
+            let node = match weak.upgrade() {
+                Some(node) => node,
+                None => break,
+            };
+            if !node.is_online() {
+                continue;
+            }
+            if let Some(endpoint) = node.candidate() {
+                node.dial(endpoint, true).await;
+            }
+        }
+    }
+
+    pub fn process_block(&self, hash: Hash, bytes: Box<[u8]>) -> Result<(), Error> {
+        let tx_hashes = self.block_db.process(hash, bytes, &self.coin_db)?;
+        let mut tx_pool = self.tx_pool.write().unwrap();
+        tx_pool.block_connected(&tx_hashes);
+        Ok(())
+    }
+
+    /**
+     * Return transactions of rolled back blocks, given the tip first, to the pool
+     */
+    pub(crate) fn blocks_disconnected(&self, hashes: &[Hash]) {
+        let transactions = hashes
+            .iter()
+            .rev()
+            .flat_map(|&hash| self.block_transactions(hash))
+            .collect::<Vec<_>>();
+        let mut tx_pool = self.tx_pool.write().unwrap();
+        tx_pool.block_disconnected(transactions, SystemClock::millis());
+    }
+
+    pub(crate) fn blocks_reconnected(&self, hashes: &[Hash]) {
+        let tx_hashes = hashes
+            .iter()
+            .flat_map(|&hash| self.block_transactions(hash))
+            .map(|(hash, _)| hash)
+            .collect::<Vec<_>>();
+        let mut tx_pool = self.tx_pool.write().unwrap();
+        tx_pool.block_connected(&tx_hashes);
+    }
+
+    fn block_transactions(&self, hash: Hash) -> Vec<(Hash, Box<[u8]>)> {
+        let Some((block, _)) = self.block_db.get(hash) else {
+            error!(self.logger, "{hash} not found");
+            return Vec::new();
+        };
+        block
+            .raw_transactions()
+            .iter()
+            .map(|bytes| {
+                let hash = Transaction::compute_hash(bytes).expect("Hashable tx");
+                (hash, bytes.clone())
+            })
+            .collect()
+    }
+
     pub fn broadcast_tx(&self, hash: Hash, bytes: &[u8]) -> Result<(), Error> {
         let now = SystemClock::millis();
         let result = {
@@ -373,8 +859,37 @@ impl Node {
         }
     }
 
+    fn load(logger: &Logger, state_dir: &Path) -> Vec<Endpoint> {
+        let mut file = match File::open(state_dir.join(DATA_FILENAME)) {
+            Ok(file) => BufReader::new(file),
+            Err(err) => {
+                if err.kind() != ErrorKind::NotFound {
+                    warn!(logger, "Can't read {DATA_FILENAME}: {err}");
+                }
+                return Vec::new();
+            }
+        };
+        let mut version = [0u8; 4];
+        if let Err(err) = file.read_exact(&mut version) {
+            warn!(logger, "Can't read {DATA_FILENAME}: {err}");
+            return Vec::new();
+        }
+        let version = u32::from_be_bytes(version);
+        if version != DATA_VERSION {
+            warn!(logger, "Unknown {DATA_FILENAME} version {version}");
+            return Vec::new();
+        }
+        match from_read::<Persistent, _>(&mut file) {
+            Ok(persistent) => persistent.peers,
+            Err(err) => {
+                warn!(logger, "Can't read {DATA_FILENAME}: {err}");
+                Vec::new()
+            }
+        }
+    }
+
     pub fn dispose(self: Arc<Self>) {
-        let mut connections = self.connections.write().unwrap();
+        let connections = self.connections.read().unwrap();
         info!(self.logger, "Closing {} p2p connections", connections.len());
         let mut peers = Vec::with_capacity(connections.len());
         for connection in connections.iter() {
@@ -384,7 +899,8 @@ impl Node {
             }
             connection.close();
         }
-        connections.clear();
+        drop(connections);
+        self.tx_pool.read().unwrap().save(&self.state_dir);
         info!(self.logger, "Saving node state");
         let persistent = Persistent { peers };
         if let Err(err) = replace(&self.state_dir, DATA_FILENAME, |buffered| {
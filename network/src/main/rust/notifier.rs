@@ -0,0 +1,77 @@
+/*
+ * Copyright (c) 2026 Pavel Vasin
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Lesser General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use blacknet_kernel::blake2b::Hash;
+use blacknet_time::Milliseconds;
+use std::sync::Arc;
+use tokio::sync::broadcast::{Receiver, Sender, channel};
+
+const CAPACITY: usize = 1024;
+
+#[derive(Clone)]
+pub enum Notification {
+    Block {
+        hash: Hash,
+        height: u32,
+        bytes: Arc<[u8]>,
+    },
+    Transaction {
+        hash: Hash,
+        time: Milliseconds,
+        bytes: Arc<[u8]>,
+    },
+}
+
+/**
+ * Fans out connected blocks and accepted transactions to subscribers such as RPC.
+ * Slow subscribers lag behind and lose the oldest notifications.
+ */
+pub struct Notifier {
+    sender: Sender<Notification>,
+}
+
+impl Notifier {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            sender: channel(CAPACITY).0,
+        })
+    }
+
+    pub fn subscribe(&self) -> Receiver<Notification> {
+        self.sender.subscribe()
+    }
+
+    pub fn block(&self, hash: Hash, height: u32, bytes: &[u8]) {
+        if self.sender.receiver_count() != 0 {
+            let _ = self.sender.send(Notification::Block {
+                hash,
+                height,
+                bytes: bytes.into(),
+            });
+        }
+    }
+
+    pub fn transaction(&self, hash: Hash, time: Milliseconds, bytes: &[u8]) {
+        if self.sender.receiver_count() != 0 {
+            let _ = self.sender.send(Notification::Transaction {
+                hash,
+                time,
+                bytes: bytes.into(),
+            });
+        }
+    }
+}
@@ -52,6 +52,18 @@ impl Blocks {
             blocks: Default::default(),
         }
     }
+
+    pub fn hashes(&self) -> &[Hash] {
+        &self.hashes
+    }
+
+    pub fn blocks(&self) -> &[Box<[u8]>] {
+        &self.blocks
+    }
+
+    pub fn into_blocks(self) -> Vec<Box<[u8]>> {
+        self.blocks
+    }
 }
 
 impl Packet for Blocks {
@@ -29,6 +29,12 @@ pub struct GetBlocks {
     checkpoint: Hash,
 }
 
+impl GetBlocks {
+    pub const fn new(best: Hash, checkpoint: Hash) -> Self {
+        Self { best, checkpoint }
+    }
+}
+
 impl Packet for GetBlocks {
     fn kind() -> PacketKind {
         PacketKind::GetBlocks
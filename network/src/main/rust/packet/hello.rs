@@ -169,6 +169,7 @@ impl Packet for Hello {
                     connection.agent().load(),
                 );
                 connection.set_state(State::IncomingConnected);
+                node.incoming_connected(connection);
             }
             State::OutgoingWaiting => {
                 info!(
@@ -209,7 +210,7 @@ impl Packet for Hello {
     }
 }
 
-fn send_handshake(connection: &Connection) -> Result<(), SerializationError> {
+pub(crate) fn send_handshake(connection: &Connection) -> Result<(), SerializationError> {
     let node = connection.node();
 
     let mut hello = Hello::default();
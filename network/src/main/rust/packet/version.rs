@@ -99,6 +99,7 @@ impl Packet for Version {
                         connection.agent().load(),
                     );
                     connection.set_state(State::IncomingConnected);
+                    node.incoming_connected(connection);
                 } else {
                     // connected to self or bad luck
                     connection.close();
@@ -15,6 +15,7 @@
  * along with this program. If not, see <https://www.gnu.org/licenses/>.
  */
 
+use crate::banlist::BanList;
 use crate::endpoint::Endpoint;
 use crate::settings::Settings;
 use blacknet_compat::{Mode, XDGDirectories};
@@ -43,6 +44,7 @@ const FILE_NAME: &str = "peers.dat";
 pub struct PeerTable {
     logger: Logger,
     settings: Arc<Settings>,
+    ban_list: Arc<BanList>,
     data_dir: PathBuf,
     peers: RwLock<HashMap<Endpoint, Entry>>,
 }
@@ -53,10 +55,12 @@ impl PeerTable {
         dirs: &XDGDirectories,
         log_manager: &LogManager,
         settings: Arc<Settings>,
+        ban_list: Arc<BanList>,
     ) -> Result<Arc<Self>, Box<dyn Error>> {
         let peer_table = Self {
             logger: log_manager.logger("PeerTable")?,
             settings,
+            ban_list,
             data_dir: dirs.data().to_owned(),
             peers: RwLock::new(HashMap::with_capacity(MAX_SIZE)),
         };
@@ -123,6 +127,13 @@ impl PeerTable {
             .or_insert_with(|| Entry::with_connected(time, user_agent));
     }
 
+    pub fn failed(&self, endpoint: Endpoint, time: Milliseconds) {
+        let mut peers = self.peers.write().unwrap();
+        if let Some(entry) = peers.get_mut(&endpoint) {
+            entry.failed(time);
+        }
+    }
+
     pub fn try_contact(&self, endpoint: Endpoint) -> bool {
         if endpoint.is_local() || endpoint.is_private() {
             return false;
@@ -255,7 +266,7 @@ impl PeerTable {
         let mut candidates = Vec::<(&Endpoint, &Entry, f32)>::with_capacity(peers.len());
         let now = SystemClock::millis();
         for (endpoint, entry) in peers.iter() {
-            if predicate(endpoint, entry) {
+            if predicate(endpoint, entry) && !self.ban_list.is_banned(*endpoint) {
                 candidates.push((endpoint, entry, entry.chance(now)));
             }
         }
@@ -416,6 +427,11 @@ impl Entry {
         self.last_try = time;
     }
 
+    const fn failed(&mut self, time: Milliseconds) {
+        self.attempts += 1;
+        self.last_try = time;
+    }
+
     fn contact(&self) -> bool {
         self.in_contact
             .compare_exchange(false, true, Ordering::Relaxed, Ordering::Relaxed)
@@ -16,8 +16,9 @@
  */
 
 use crate::endpoint::{Endpoint, ipv4_any, ipv6_any};
-use crate::i2psam::SAM;
+use crate::i2psam::{Error as I2PError, SAM, Streams};
 use crate::natpmp::natpmp_forward;
+use crate::node::Node;
 use crate::peertable::PeerTable;
 use crate::settings::Settings;
 use crate::torcontroller::TorController;
@@ -26,8 +27,9 @@ use blacknet_log::{LogManager, Logger, info, warn};
 use std::cmp::min;
 use std::collections::HashSet;
 use std::error::Error;
-use std::sync::{Arc, RwLock};
-use tokio::net::TcpListener;
+use std::sync::{Arc, RwLock, Weak};
+use tokio::io::BufStream;
+use tokio::net::{TcpListener, TcpStream};
 use tokio::runtime::Runtime;
 use tokio::sync::Mutex;
 use tokio::time::{Duration, sleep};
@@ -38,6 +40,7 @@ pub struct Router {
     listens: RwLock<HashSet<Endpoint>>,
     peer_table: Arc<PeerTable>,
     i2p_sam: Mutex<SAM>,
+    i2p_streams: RwLock<Option<Streams>>,
     tor_controller: Mutex<TorController>,
 }
 
@@ -46,36 +49,36 @@ impl Router {
         mode: &Mode,
         dirs: &XDGDirectories,
         log_manager: &LogManager,
-        runtime: &Runtime,
         settings: &Arc<Settings>,
         peer_table: Arc<PeerTable>,
     ) -> Result<Arc<Self>, Box<dyn Error>> {
-        let router = Arc::new(Self {
+        Ok(Arc::new(Self {
             logger: log_manager.logger("Router")?,
             settings: settings.clone(),
             listens: RwLock::new(HashSet::new()),
             peer_table,
             i2p_sam: Mutex::new(SAM::new(mode, dirs, log_manager, settings.clone())?),
+            i2p_streams: RwLock::new(None),
             tor_controller: Mutex::new(TorController::new(dirs, log_manager, settings.clone())?),
-        });
+        }))
+    }
 
-        if settings.ipv6 || settings.ipv4 {
-            runtime.spawn(router.clone().listen_ip());
-            if settings.natpmp {
-                runtime.spawn(router.clone().forward_natpmp());
+    pub fn launch(self: &Arc<Self>, runtime: &Runtime, node: &Weak<Node>) {
+        if self.settings.ipv6 || self.settings.ipv4 {
+            runtime.spawn(self.clone().listen_ip(node.clone()));
+            if self.settings.natpmp {
+                runtime.spawn(self.clone().forward_natpmp());
             }
         }
-        if settings.tor {
-            runtime.spawn(router.clone().listen_tor());
+        if self.settings.tor {
+            runtime.spawn(self.clone().listen_tor());
         }
-        if settings.i2p {
-            runtime.spawn(router.clone().listen_i2p());
+        if self.settings.i2p {
+            runtime.spawn(self.clone().listen_i2p(node.clone()));
         }
-
-        Ok(router)
     }
 
-    async fn listen_ip(self: Arc<Self>) {
+    async fn listen_ip(self: Arc<Self>, node: Weak<Node>) {
         let mut timeout = Self::INIT_TIMEOUT;
         let endpoint = if self.settings.ipv6 {
             ipv6_any(self.settings.port)
@@ -91,7 +94,22 @@ impl Router {
                     self.add_listener(endpoint);
                     loop {
                         match listener.accept().await {
-                            Ok((_socket, _addr)) => todo!(),
+                            Ok((socket, addr)) => {
+                                let remote_endpoint = Endpoint::from(addr);
+                                let local_endpoint = match socket.local_addr() {
+                                    Ok(addr) => self.local_endpoint(addr.into(), remote_endpoint),
+                                    Err(msg) => {
+                                        warn!(self.logger, "{msg}");
+                                        continue;
+                                    }
+                                };
+                                match node.upgrade() {
+                                    Some(node) => {
+                                        node.accept(socket, remote_endpoint, local_endpoint)
+                                    }
+                                    None => return,
+                                }
+                            }
                             Err(msg) => {
                                 warn!(self.logger, "{msg}");
                                 break;
@@ -132,7 +150,7 @@ impl Router {
         }
     }
 
-    async fn listen_i2p(self: Arc<Self>) {
+    async fn listen_i2p(self: Arc<Self>, node: Weak<Node>) {
         let mut timeout = Self::INIT_TIMEOUT;
         let mut i2p_sam = self.i2p_sam.lock().await;
         loop {
@@ -140,8 +158,12 @@ impl Router {
                 Ok(mut session) => {
                     timeout = Self::INIT_TIMEOUT;
                     self.add_listener(session.endpoint());
+                    let streams = session.streams();
+                    *self.i2p_streams.write().unwrap() = Some(streams.clone());
+                    let acceptor = tokio::spawn(self.clone().accept_i2p(streams, node.clone()));
                     session.hung().await;
-                    //TODO accept
+                    acceptor.abort();
+                    *self.i2p_streams.write().unwrap() = None;
                     info!(self.logger, "Closing I2P session");
                     self.remove_listener(session.endpoint());
                 }
@@ -155,6 +177,51 @@ impl Router {
         }
     }
 
+    async fn accept_i2p(self: Arc<Self>, streams: Streams, node: Weak<Node>) {
+        loop {
+            match streams.accept().await {
+                Ok((stream, remote_endpoint)) => match node.upgrade() {
+                    Some(node) => node.accept(stream, remote_endpoint, streams.endpoint()),
+                    None => break,
+                },
+                Err(msg) => {
+                    warn!(self.logger, "{msg}");
+                    sleep(Self::ACCEPT_TIMEOUT).await;
+                }
+            }
+        }
+    }
+
+    pub fn is_i2p_ready(&self) -> bool {
+        self.i2p_streams.read().unwrap().is_some()
+    }
+
+    pub async fn connect_i2p(
+        &self,
+        endpoint: Endpoint,
+    ) -> Result<(BufStream<TcpStream>, Endpoint), I2PError> {
+        let streams = match &*self.i2p_streams.read().unwrap() {
+            Some(streams) => streams.clone(),
+            None => return Err("I2P session is not ready".into()),
+        };
+        let stream = streams.connect(endpoint).await?;
+        Ok((stream, streams.endpoint()))
+    }
+
+    // Tor forwards connections of onion service to loopback
+    fn local_endpoint(&self, local_endpoint: Endpoint, remote_endpoint: Endpoint) -> Endpoint {
+        if remote_endpoint.is_local() {
+            let listens = self.listens.read().unwrap();
+            if let Some(&endpoint) = listens
+                .iter()
+                .find(|endpoint| matches!(endpoint, Endpoint::TORv3 { .. }))
+            {
+                return endpoint;
+            }
+        }
+        local_endpoint
+    }
+
     async fn forward_natpmp(self: Arc<Self>) {
         match natpmp_forward(self.settings.port).await {
             Ok(endpoint) => {
@@ -198,6 +265,7 @@ impl Router {
         &self.listens
     }
 
+    const ACCEPT_TIMEOUT: Duration = Duration::from_secs(1);
     const INIT_TIMEOUT: Duration = Duration::from_secs(60);
     const MAX_TIMEOUT: Duration = Duration::from_secs(2 * 60 * 60);
 }
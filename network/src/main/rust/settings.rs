@@ -15,8 +15,9 @@
  * along with this program. If not, see <https://www.gnu.org/licenses/>.
  */
 
-use blacknet_compat::Mode;
+use blacknet_compat::{Config, ConfigError, Mode};
 use blacknet_kernel::amount::Amount;
+use std::path::PathBuf;
 
 pub struct Settings {
     pub port: u16,
@@ -32,9 +33,14 @@ pub struct Settings {
     pub i2p_sam_port: u16,
     pub tor_control_host: String,
     pub tor_control_port: u16,
+    pub tor_proxy_host: String,
+    pub tor_proxy_port: u16,
     pub db_cache: u64,
     pub tx_pool_size: usize,
     pub min_relay_fee_rate: Amount,
+    pub bootstrap: Option<PathBuf>,
+    pub address_index: bool,
+    pub tx_index: bool,
 }
 
 impl Settings {
@@ -53,9 +59,49 @@ impl Settings {
             i2p_sam_port: 7656,
             tor_control_host: "127.0.0.1".to_owned(),
             tor_control_port: 9051,
+            tor_proxy_host: "127.0.0.1".to_owned(),
+            tor_proxy_port: 9050,
             db_cache: 256 * 1024 * 1024,
             tx_pool_size: 128 * 1024 * 1024,
             min_relay_fee_rate: Amount::new(100000), // 0.001
+            bootstrap: None,
+            address_index: false,
+            tx_index: false,
         }
     }
+
+    pub fn configure(&mut self, config: &mut Config) -> Result<(), ConfigError> {
+        config.take_if("port", &mut self.port, |&port| port != 0)?;
+        config.take("ipv4", &mut self.ipv4)?;
+        config.take("ipv6", &mut self.ipv6)?;
+        config.take("tor", &mut self.tor)?;
+        config.take("i2p", &mut self.i2p)?;
+        config.take("natpmp", &mut self.natpmp)?;
+        config.take("incoming_connections", &mut self.incoming_connections)?;
+        config.take("outgoing_connections", &mut self.outgoing_connections)?;
+        config.take("log_endpoint", &mut self.log_endpoint)?;
+        config.take("i2p_sam_host", &mut self.i2p_sam_host)?;
+        config.take_if("i2p_sam_port", &mut self.i2p_sam_port, |&port| port != 0)?;
+        config.take("tor_control_host", &mut self.tor_control_host)?;
+        config.take_if("tor_control_port", &mut self.tor_control_port, |&port| {
+            port != 0
+        })?;
+        config.take("tor_proxy_host", &mut self.tor_proxy_host)?;
+        config.take_if("tor_proxy_port", &mut self.tor_proxy_port, |&port| {
+            port != 0
+        })?;
+        config.take_if("db_cache", &mut self.db_cache, |&size| size != 0)?;
+        config.take_if("tx_pool_size", &mut self.tx_pool_size, |&size| size != 0)?;
+        let mut min_relay_fee_rate = self.min_relay_fee_rate.value();
+        config.take("min_relay_fee_rate", &mut min_relay_fee_rate)?;
+        self.min_relay_fee_rate = Amount::new(min_relay_fee_rate);
+        let mut bootstrap = PathBuf::new();
+        config.take("bootstrap", &mut bootstrap)?;
+        if !bootstrap.as_os_str().is_empty() {
+            self.bootstrap = Some(bootstrap);
+        }
+        config.take("address_index", &mut self.address_index)?;
+        config.take("tx_index", &mut self.tx_index)?;
+        Ok(())
+    }
 }
@@ -15,50 +15,274 @@
  * along with this program. If not, see <https://www.gnu.org/licenses/>.
  */
 
+use crate::coindb::{CoinDB, State as CoinState};
+use crate::node::Node;
 use blacknet_kernel::amount::Amount;
-use blacknet_kernel::ed25519::{PublicKey, SecretKey};
-use blacknet_log::{Error as LogError, LogManager, Logger, info};
-use blacknet_time::Seconds;
+use blacknet_kernel::blake2b::Hash;
+use blacknet_kernel::block::{BLOCK_VERSION, Block};
+use blacknet_kernel::ed25519::{PublicKey, SecretKey, to_public_key};
+use blacknet_kernel::proofofstake::{expected_time, network_weight, time_slot, verify};
+use blacknet_log::{Error as LogError, LogManager, Logger, info, warn};
+use blacknet_serialization::format::to_size;
+use blacknet_time::{Seconds, SystemClock};
 use core::fmt;
+use std::sync::{Arc, Mutex, Weak};
+use tokio::sync::Notify;
+use tokio::time::{Duration, sleep};
+use zeroize::{Zeroize, Zeroizing};
 
 pub struct Staker {
     logger: Logger,
-    state: State,
+    state: Mutex<State>,
+    stakeholders: Mutex<Stakeholders>,
+    started: Notify,
+    coin_db: Arc<CoinDB>,
 }
 
 impl Staker {
-    pub fn new(log_manager: &LogManager) -> core::result::Result<Self, LogError> {
+    pub fn new(
+        log_manager: &LogManager,
+        coin_db: Arc<CoinDB>,
+    ) -> core::result::Result<Self, LogError> {
         Ok(Self {
             logger: log_manager.logger("Staker")?,
-            state: State::Initializing,
+            state: Mutex::new(State::Initializing),
+            stakeholders: Mutex::new(Stakeholders {
+                list: Vec::new(),
+                hash_counter: 0,
+                start_time: Seconds::ZERO,
+            }),
+            started: Notify::new(),
+            coin_db,
         })
     }
 
-    #[expect(unused_variables)]
-    pub const fn start_staking(&self, secret_key: &SecretKey) -> bool {
-        todo!();
+    pub fn start_staking(&self, secret_key: &SecretKey) -> bool {
+        let public_key = to_public_key(*secret_key);
+        let mut stakeholders = self.stakeholders.lock().unwrap();
+        if stakeholders.list.iter().any(|i| i.public_key == public_key) {
+            info!(self.logger, "Stakeholder is already active");
+            return false;
+        }
+        let state = self.coin_db.state();
+        let stake = self.staking_balance(public_key, state.height() + 1);
+        if stake == Amount::ZERO {
+            warn!(self.logger, "Staking balance is zero");
+        }
+        stakeholders.list.push(Stakeholder {
+            secret_key: *secret_key,
+            public_key,
+            last_block: state.block_hash(),
+            stake,
+        });
+        if stakeholders.list.len() == 1 {
+            stakeholders.hash_counter = 0;
+            stakeholders.start_time = SystemClock::secs();
+            self.set_state(State::Started);
+            self.started.notify_one();
+        }
+        true
     }
 
-    #[expect(unused_variables)]
-    pub const fn stop_staking(&self, secret_key: &SecretKey) -> bool {
-        todo!();
+    pub fn stop_staking(&self, secret_key: &SecretKey) -> bool {
+        let public_key = to_public_key(*secret_key);
+        let mut stakeholders = self.stakeholders.lock().unwrap();
+        match stakeholders
+            .list
+            .iter()
+            .position(|i| i.public_key == public_key)
+        {
+            Some(index) => {
+                stakeholders.list.remove(index);
+                if stakeholders.list.is_empty() {
+                    self.set_state(State::Stopped);
+                }
+                true
+            }
+            None => {
+                info!(self.logger, "Stakeholder is not active");
+                false
+            }
+        }
+    }
+
+    pub fn is_staking(&self, secret_key: &SecretKey) -> bool {
+        let public_key = to_public_key(*secret_key);
+        let stakeholders = self.stakeholders.lock().unwrap();
+        stakeholders.list.iter().any(|i| i.public_key == public_key)
+    }
+
+    pub fn stats(&self, public_key: &Option<PublicKey>) -> StakerStats {
+        let state = self.coin_db.state();
+        let pos_version = state.pos_version(self.coin_db.requires_network());
+        let stakeholders = self.stakeholders.lock().unwrap();
+        let (staking_accounts, weight) = stakeholders
+            .list
+            .iter()
+            .filter(|i| public_key.is_none_or(|public_key| public_key == i.public_key))
+            .fold((0, Amount::ZERO), |(n, weight), i| {
+                (n + 1, weight.checked_add(i.stake).unwrap_or(Amount::MAX))
+            });
+        let elapsed = SystemClock::secs() - stakeholders.start_time;
+        let hash_rate = if staking_accounts != 0 && elapsed > Seconds::ZERO {
+            stakeholders.hash_counter as f64 / elapsed.value() as f64
+        } else {
+            0.0
+        };
+        let network_weight = network_weight(pos_version, state.difficulty());
+        StakerStats {
+            staking_accounts,
+            hash_rate,
+            weight,
+            network_weight,
+            expected_time: expected_time(pos_version, weight, network_weight),
+        }
+    }
+
+    pub async fn implementation(weak: Weak<Node>) {
+        loop {
+            let node = match weak.upgrade() {
+                Some(node) => node,
+                None => break,
+            };
+            let staker = node.staker();
+            if staker.stakeholders.lock().unwrap().list.is_empty() {
+                staker.started.notified().await;
+                continue;
+            }
+            sleep(staker.until_next_time_slot()).await;
+            staker.stake(&node).await;
+        }
+    }
+
+    fn until_next_time_slot(&self) -> Duration {
+        let state = self.coin_db.state();
+        let pos_version = state.pos_version(self.coin_db.requires_network());
+        let time_slot = time_slot(pos_version).value() * 1000;
+        let now = SystemClock::millis().value();
+        Duration::from_millis((time_slot - now % time_slot) as u64)
+    }
+
+    async fn stake(&self, node: &Node) {
+        if self.coin_db.requires_network() {
+            if !node.is_online() {
+                self.set_state(State::AwaitingOnline);
+                return;
+            }
+            if node.is_initial_synchronization() {
+                self.set_state(State::AwaitingSync);
+                return;
+            }
+        }
+        self.set_state(State::Staking);
+
+        let state = self.coin_db.state();
+        let pos_version = state.pos_version(self.coin_db.requires_network());
+        let now = SystemClock::secs();
+        let time_slot = now - now % time_slot(pos_version);
+        if time_slot <= state.block_time() {
+            return;
+        }
+
+        let generators = {
+            let mut stakeholders = self.stakeholders.lock().unwrap();
+            let Stakeholders {
+                list, hash_counter, ..
+            } = &mut *stakeholders;
+            let height = state.height() + 1;
+            let mut generators = Vec::new();
+            for stakeholder in list.iter_mut() {
+                if stakeholder.last_block != state.block_hash() {
+                    stakeholder.stake = self.staking_balance(stakeholder.public_key, height);
+                    stakeholder.last_block = state.block_hash();
+                }
+                *hash_counter += 1;
+                if verify(
+                    pos_version,
+                    time_slot,
+                    stakeholder.public_key,
+                    state.nxtrng(),
+                    state.difficulty(),
+                    state.block_time(),
+                    stakeholder.stake,
+                )
+                .is_ok()
+                {
+                    generators.push((
+                        stakeholder.public_key,
+                        Zeroizing::new(stakeholder.secret_key),
+                    ));
+                }
+            }
+            generators
+        };
+
+        for (public_key, secret_key) in generators {
+            if self
+                .mint(node, &state, time_slot, public_key, *secret_key)
+                .await
+            {
+                break;
+            }
+        }
     }
 
-    #[expect(unused_variables)]
-    pub const fn is_staking(&self, secret_key: &SecretKey) -> bool {
-        todo!();
+    async fn mint(
+        &self,
+        node: &Node,
+        state: &CoinState,
+        time: Seconds,
+        public_key: PublicKey,
+        mut secret_key: SecretKey,
+    ) -> bool {
+        let mut block = Block::new(state.block_hash(), time, public_key);
+        // reserve space for the length of transactions
+        let max_size = state.max_block_size() as usize
+            - to_size(&block).expect("Serializable block")
+            - size_of::<u32>();
+        let transactions = node.tx_pool().read().unwrap().fill(max_size);
+        let mut minted = false;
+        if !transactions.is_empty() {
+            let mut block = Block::with_all(
+                BLOCK_VERSION,
+                state.block_hash(),
+                time,
+                public_key,
+                Hash::ZERO,
+                Default::default(),
+                transactions.into_boxed_slice(),
+            );
+            let (hash, bytes) = block.sign(secret_key);
+            minted = node.broadcast_block(hash, bytes).await;
+            if minted {
+                info!(self.logger, "Staked {hash}");
+            }
+        }
+        if !minted {
+            // retry without transactions
+            let (hash, bytes) = block.sign(secret_key);
+            minted = node.broadcast_block(hash, bytes).await;
+            if minted {
+                info!(self.logger, "Staked {hash}");
+            }
+        }
+        secret_key.zeroize();
+        minted
     }
 
-    #[expect(unused_variables)]
-    pub const fn stats(&self, public_key: &Option<PublicKey>) -> StakerStats {
-        todo!();
+    fn staking_balance(&self, public_key: PublicKey, height: u32) -> Amount {
+        match self.coin_db.account(public_key) {
+            Some(account) => account.staking_balance(height),
+            None => Amount::ZERO,
+        }
     }
 
-    fn set_state(&mut self, state: State) {
-        if self.state == state {
+    fn set_state(&self, state: State) {
+        let mut current = self.state.lock().unwrap();
+        if *current == state {
             return;
         }
-        self.state = state;
+        *current = state;
         info!(self.logger, "{state}");
     }
 }
@@ -69,7 +293,6 @@ impl Drop for Staker {
     }
 }
 
-#[expect(dead_code)]
 #[derive(Clone, Copy, Eq, PartialEq)]
 enum State {
     Initializing,
@@ -81,6 +304,25 @@ enum State {
     Stopped,
 }
 
+struct Stakeholders {
+    list: Vec<Stakeholder>,
+    hash_counter: u64,
+    start_time: Seconds,
+}
+
+struct Stakeholder {
+    secret_key: SecretKey,
+    public_key: PublicKey,
+    last_block: Hash,
+    stake: Amount,
+}
+
+impl Drop for Stakeholder {
+    fn drop(&mut self) {
+        self.secret_key.zeroize();
+    }
+}
+
 impl fmt::Display for State {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
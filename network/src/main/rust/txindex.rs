@@ -0,0 +1,95 @@
+/*
+ * Copyright (c) 2026 Pavel Vasin
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Lesser General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::dbview::DBView;
+use crate::fjall::Fjall;
+use crate::genesis;
+use blacknet_kernel::blake2b::Hash;
+use blacknet_kernel::block::Block;
+use blacknet_kernel::transaction::Transaction;
+use fjall::{OwnedWriteBatch as WriteBatch, Result};
+use serde::{Deserialize, Serialize};
+
+const TIP_KEY: &str = "tip";
+
+#[derive(Clone, Copy, Deserialize, Serialize)]
+pub struct Location {
+    block: Hash,
+    position: u32,
+}
+
+impl Location {
+    pub const fn block(&self) -> Hash {
+        self.block
+    }
+
+    pub const fn position(&self) -> u32 {
+        self.position
+    }
+}
+
+/**
+ * Optional index of confirmed transactions by hash
+ */
+pub struct TxIndex {
+    locations: DBView<Hash, Location>,
+    tips: DBView<&'static str, Hash>,
+}
+
+impl TxIndex {
+    pub fn new(fjall: &Fjall) -> Result<Self> {
+        Ok(Self {
+            locations: DBView::new(fjall, "transactions")?,
+            tips: DBView::new(fjall, "txindex")?,
+        })
+    }
+
+    pub fn get(&self, hash: Hash) -> Option<Location> {
+        self.locations.get(hash)
+    }
+
+    pub fn is_synchronized(&self, tip: Hash) -> bool {
+        self.tips.get(TIP_KEY) == Some(tip)
+    }
+
+    pub fn connect(&self, batch: &mut WriteBatch, hash: Hash, block: &Block) {
+        for (position, bytes) in block.raw_transactions().iter().enumerate() {
+            let tx_hash = Transaction::compute_hash(bytes).expect("Hashable tx");
+            let location = Location {
+                block: hash,
+                position: position as u32,
+            };
+            self.locations.batch(batch, tx_hash, &location);
+        }
+        self.tips.batch(batch, TIP_KEY, &hash);
+    }
+
+    pub fn disconnect(&self, batch: &mut WriteBatch, block: &Block) {
+        for bytes in block.raw_transactions() {
+            let tx_hash = Transaction::compute_hash(bytes).expect("Hashable tx");
+            self.locations.batch_remove(batch, tx_hash);
+        }
+        self.tips.batch(batch, TIP_KEY, &block.previous());
+    }
+
+    pub fn clear(&self, batch: &mut WriteBatch) {
+        for (hash, _) in self.locations.iter() {
+            self.locations.batch_remove(batch, hash);
+        }
+        self.tips.batch(batch, TIP_KEY, &genesis::hash());
+    }
+}
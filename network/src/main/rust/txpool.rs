@@ -16,7 +16,10 @@
  */
 
 use crate::coindb::CoinDB;
+use crate::notifier::Notifier;
+use crate::rollinghashset::RollingHashSet;
 use crate::settings::Settings;
+use blacknet_io::file::replace;
 use blacknet_kernel::account::Account;
 use blacknet_kernel::amount::Amount;
 use blacknet_kernel::blake2b::Hash;
@@ -27,26 +30,74 @@ use blacknet_kernel::multisig::Multisig;
 use blacknet_kernel::transaction::{
     CoinTx, HashTimeLockContractId, MultiSignatureLockContractId, Transaction,
 };
-use blacknet_log::{Error as LogError, LogManager, Logger, debug, warn};
-use blacknet_serialization::format::from_bytes;
-use blacknet_time::{Milliseconds, Seconds};
-use std::collections::{HashMap, HashSet, hash_map::Keys};
+use blacknet_log::{Error as LogError, LogManager, Logger, debug, error, info, warn};
+use blacknet_serialization::format::{from_bytes, from_read, to_size, to_write};
+use blacknet_time::{Milliseconds, Seconds, SystemClock};
+use blacknet_wallet::scanner::{Contracts, Effects, Scanner};
+use blacknet_wallet::walletdb::WalletDB;
+use core::cmp::{Reverse, max};
+use core::error::Error as StdError;
+use core::mem::take;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufReader, ErrorKind, Read, Write};
+use std::path::Path;
 use std::sync::Arc;
 
+const MAX_REJECTS: usize = 64 * 1024;
+const MAX_AGE: Milliseconds = Milliseconds::with_days(14);
+const EXPIRY_INTERVAL: Milliseconds = Milliseconds::with_minutes(1);
+const ROLLING_FEE_HALFLIFE: Milliseconds = Milliseconds::with_hours(12);
+const FILE_VERSION: u32 = 1;
+const FILE_NAME: &str = "txpool.dat";
+
 pub struct TxPool {
     logger: Logger,
     settings: Arc<Settings>,
-    map: HashMap<Hash, Box<[u8]>>,
-    rejects: HashSet<Hash>,
+    map: HashMap<Hash, Entry>,
+    rejects: RollingHashSet<Hash>,
     data_len: usize,
     accounts: HashMap<PublicKey, Account>,
     htlcs: HashMap<HashTimeLockContractId, Option<HTLC>>,
     multisigs: HashMap<MultiSignatureLockContractId, Option<Multisig>>,
+    // last pool transaction that has touched the state
+    account_writers: HashMap<PublicKey, Hash>,
+    htlc_writers: HashMap<HashTimeLockContractId, Hash>,
+    multisig_writers: HashMap<MultiSignatureLockContractId, Hash>,
     transactions: Vec<Hash>,
     undo_accounts: HashMap<PublicKey, Option<Account>>,
     undo_htlcs: HashMap<HashTimeLockContractId, (bool, Option<HTLC>)>,
     undo_multisigs: HashMap<MultiSignatureLockContractId, (bool, Option<Multisig>)>,
+    rolling_fee_rate: (Amount, Milliseconds),
+    last_expiry: Milliseconds,
     coin_db: Arc<CoinDB>,
+    notifier: Arc<Notifier>,
+    wallet_db: Arc<WalletDB>,
+}
+
+struct Entry {
+    bytes: Box<[u8]>,
+    fee_rate: Amount,
+    time: Milliseconds,
+    // pool transactions that have to precede this one
+    depends: Vec<Hash>,
+    // and those that follow it
+    dependents: Vec<Hash>,
+    // pool state before this one, to take it off the top
+    undo: Undo,
+}
+
+#[derive(Default)]
+struct Undo {
+    // previous value and writer
+    accounts: Vec<(PublicKey, Option<Account>, Option<Hash>)>,
+    htlcs: Vec<(HashTimeLockContractId, (bool, Option<HTLC>), Option<Hash>)>,
+    multisigs: Vec<(
+        MultiSignatureLockContractId,
+        (bool, Option<Multisig>),
+        Option<Hash>,
+    )>,
 }
 
 impl TxPool {
@@ -54,21 +105,30 @@ impl TxPool {
         log_manager: &LogManager,
         settings: Arc<Settings>,
         coin_db: Arc<CoinDB>,
+        notifier: Arc<Notifier>,
+        wallet_db: Arc<WalletDB>,
     ) -> core::result::Result<Self, LogError> {
         Ok(Self {
             logger: log_manager.logger("TxPool")?,
             settings,
             map: HashMap::new(),
-            rejects: HashSet::new(),
+            rejects: RollingHashSet::new(MAX_REJECTS),
             data_len: 0,
             accounts: HashMap::new(),
             htlcs: HashMap::new(),
             multisigs: HashMap::new(),
+            account_writers: HashMap::new(),
+            htlc_writers: HashMap::new(),
+            multisig_writers: HashMap::new(),
             transactions: Vec::new(),
             undo_accounts: HashMap::new(),
             undo_htlcs: HashMap::new(),
             undo_multisigs: HashMap::new(),
+            rolling_fee_rate: (Amount::ZERO, Milliseconds::ZERO),
+            last_expiry: Milliseconds::ZERO,
             coin_db,
+            notifier,
+            wallet_db,
         })
     }
 
@@ -84,16 +144,62 @@ impl TxPool {
         self.data_len
     }
 
+    /**
+     * Per kilobyte, raised after eviction and then decaying back to `min_relay_fee_rate`
+     */
     pub fn min_fee_rate(&self) -> Amount {
-        self.settings.min_relay_fee_rate
+        max(
+            self.settings.min_relay_fee_rate,
+            self.rolling_fee_rate(SystemClock::millis()),
+        )
     }
 
-    pub fn hashes(&self) -> Keys<'_, Hash, Box<[u8]>> {
+    pub fn hashes(&self) -> impl Iterator<Item = &Hash> {
         self.map.keys()
     }
 
     pub fn get_raw(&self, hash: Hash) -> Option<&[u8]> {
-        self.map.get(&hash).map(|x| &**x)
+        self.map.get(&hash).map(|entry| &*entry.bytes)
+    }
+
+    /**
+     * Highest fee rate first, along with the transactions they depend on
+     */
+    pub fn fill(&self, max_size: usize) -> Vec<Box<[u8]>> {
+        let mut candidates = self.map.iter().collect::<Vec<_>>();
+        candidates.sort_by_key(|(_, entry)| Reverse(entry.fee_rate));
+        let mut size = 0;
+        let mut selected = HashSet::<Hash>::new();
+        for (&hash, _) in candidates {
+            if selected.contains(&hash) {
+                continue;
+            }
+            let mut package = HashSet::<Hash>::new();
+            let mut package_size = 0;
+            let mut stack = vec![hash];
+            while let Some(hash) = stack.pop() {
+                if selected.contains(&hash) || package.contains(&hash) {
+                    continue;
+                }
+                // dependency may have left the pool
+                if let Some(entry) = self.map.get(&hash) {
+                    package.insert(hash);
+                    package_size += to_size(&entry.bytes).expect("Serializable tx");
+                    stack.extend(entry.depends.iter().copied());
+                }
+            }
+            if size + package_size > max_size {
+                continue;
+            }
+            size += package_size;
+            selected.extend(package);
+        }
+        // order of acceptance satisfies dependencies
+        self.transactions
+            .iter()
+            .filter(|hash| selected.contains(hash))
+            .map(|hash| self.map[hash].bytes.clone())
+            .collect()
     }
 
     pub fn is_interesting(&self, hash: Hash) -> bool {
@@ -113,48 +219,491 @@ impl TxPool {
         if self.map.contains_key(&hash) {
             return Err(Error::already_have(hash.to_string()));
         }
-        if self.data_len + bytes.len() > self.settings.tx_pool_size {
-            if remote {
-                return Err(Error::in_future("TxPool is full"));
-            } else {
-                warn!(self.logger, "TxPool is full");
-            }
+        if time >= self.last_expiry + EXPIRY_INTERVAL {
+            self.expire(time);
         }
-        let result = self.process_impl_with_fee(hash, bytes, time);
+        let tx = match from_bytes::<Transaction>(bytes, false) {
+            Ok(tx) => tx,
+            Err(err) => {
+                self.rejects.insert(hash);
+                return Err(err.into());
+            }
+        };
+        // minimum fee rate moves, so it's not a reject
+        self.check_fee(bytes.len() as u32, tx.fee())?;
+        let result = self.process_impl_with_fee(hash, &tx, bytes, time);
         if matches!(result, Err(Error::Invalid(_)) | Err(Error::InFuture(_))) {
             self.rejects.insert(hash);
         }
-        result
+        let (mut scanner, effects) = result?;
+        if self.data_len > self.settings.tx_pool_size {
+            // own transactions are kept
+            self.trim(time, if remote { None } else { Some(hash) });
+            if !self.map.contains_key(&hash) {
+                return Err(Error::in_future("TxPool is full"));
+            }
+        }
+        scanner.record(effects, bytes);
+        self.wallet_db.commit(&mut scanner, None);
+        self.notifier.transaction(hash, time, bytes);
+        debug!(self.logger, "Accepted {hash}");
+        Ok(tx.fee())
+    }
+
+    /**
+     * Write transactions in order of acceptance
+     */
+    pub fn save(&self, state_dir: &Path) {
+        let persistent = self
+            .transactions
+            .iter()
+            .map(|hash| {
+                let entry = &self.map[hash];
+                Persistent {
+                    time: entry.time,
+                    bytes: entry.bytes.clone(),
+                }
+            })
+            .collect::<Vec<Persistent>>();
+        if let Err(err) = replace(state_dir, FILE_NAME, |buffered| {
+            let version = FILE_VERSION.to_be_bytes();
+            buffered.write_all(&version)?;
+            to_write(&persistent, buffered)
+        }) {
+            error!(self.logger, "Can't write {FILE_NAME}: {err}");
+        } else {
+            info!(self.logger, "Saved {} transactions", persistent.len());
+        }
+    }
+
+    /**
+     * Process saved transactions, dropping those that are no longer valid
+     */
+    pub fn load(&mut self, state_dir: &Path) {
+        let persistent = match Self::read(state_dir) {
+            Ok(persistent) => persistent,
+            Err(err) => {
+                warn!(self.logger, "Can't read {FILE_NAME}: {err}");
+                return;
+            }
+        };
+        let now = SystemClock::millis();
+        let mut loaded = 0;
+        for Persistent { time, bytes } in persistent {
+            if time + MAX_AGE < now {
+                continue;
+            }
+            let Some(hash) = Transaction::compute_hash(&bytes) else {
+                info!(self.logger, "Dropped unhashable tx");
+                continue;
+            };
+            // not relayed by a peer, so not trimmed away on the way in
+            match self.process(hash, &bytes, time, false) {
+                Ok(_) => loaded += 1,
+                Err(err) => info!(self.logger, "Dropped {hash}: {err}"),
+            }
+        }
+        if loaded != 0 {
+            info!(self.logger, "Loaded {loaded} transactions");
+        }
     }
 
+    fn read(state_dir: &Path) -> core::result::Result<Vec<Persistent>, Box<dyn StdError>> {
+        let mut file = match File::open(state_dir.join(FILE_NAME)) {
+            Ok(file) => BufReader::new(file),
+            Err(err) => {
+                if err.kind() == ErrorKind::NotFound {
+                    // first run or unlinked file
+                    return Ok(Vec::new());
+                } else {
+                    return Err(Box::new(err));
+                }
+            }
+        };
+        let mut version = [0u8; 4];
+        file.read_exact(&mut version)?;
+        let version = u32::from_be_bytes(version);
+        if version != FILE_VERSION {
+            return Err(format!("Unknown version {version}").into());
+        }
+        Ok(from_read(&mut file)?)
+    }
+
+    /**
+     * Drop confirmed transactions and those that no longer apply on top of `CoinDB` state
+     */
+    pub fn block_connected(&mut self, tx_hashes: &[Hash]) {
+        let confirmed = tx_hashes
+            .iter()
+            .copied()
+            .filter(|hash| self.map.contains_key(hash))
+            .collect::<HashSet<Hash>>();
+        if self.map.is_empty() {
+            return;
+        }
+        self.transactions.retain(|hash| !confirmed.contains(hash));
+        for hash in confirmed.iter() {
+            let entry = self.map.remove(hash).unwrap();
+            self.data_len -= entry.bytes.len();
+        }
+        // state underneath has changed
+        self.rebuild();
+    }
+
+    /**
+     * Transactions of disconnected blocks, in chain order, go ahead of the pool ones
+     */
+    pub fn block_disconnected(&mut self, transactions: Vec<(Hash, Box<[u8]>)>, time: Milliseconds) {
+        let mut order = Vec::with_capacity(transactions.len() + self.transactions.len());
+        for (hash, bytes) in transactions {
+            if self.map.contains_key(&hash) {
+                continue;
+            }
+            let fee = match from_bytes::<Transaction>(&bytes, false) {
+                Ok(tx) => tx.fee(),
+                Err(err) => {
+                    error!(self.logger, "{err} tx {hash}");
+                    continue;
+                }
+            };
+            self.rejects.remove(&hash);
+            self.data_len += bytes.len();
+            self.map.insert(
+                hash,
+                Entry {
+                    fee_rate: fee_rate(fee, bytes.len()),
+                    bytes,
+                    time,
+                    depends: Vec::new(),
+                    dependents: Vec::new(),
+                    undo: Undo::default(),
+                },
+            );
+            order.push(hash);
+        }
+        if order.is_empty() {
+            return;
+        }
+        order.append(&mut self.transactions);
+        self.transactions = order;
+        self.rebuild();
+        if self.data_len > self.settings.tx_pool_size {
+            self.trim(time, None);
+        }
+    }
+
+    /**
+     * Adds transaction to the pool, returns what wallets record if it stays there
+     */
     fn process_impl_with_fee(
         &mut self,
         hash: Hash,
+        tx: &Transaction,
         bytes: &[u8],
-        _time: Milliseconds,
-    ) -> Result<Amount> {
-        let tx = from_bytes::<Transaction>(bytes, false)?;
+        time: Milliseconds,
+    ) -> Result<(Scanner, Effects)> {
         let fee = tx.fee();
-        self.check_fee(bytes.len() as u32, fee)?;
-        let result = self.process_transaction_impl(&tx, hash);
-        self.undo_impl(result)?;
-        self.map.insert(hash, bytes.into());
+        let scanner = self.wallet_db.scanner();
+        let effects = scanner.transaction(&*self, tx, hash, None, to_seconds(time));
+        let (depends, undo) = self.apply(tx, hash)?;
+        self.link(hash, &depends);
+        self.map.insert(
+            hash,
+            Entry {
+                bytes: bytes.into(),
+                fee_rate: fee_rate(fee, bytes.len()),
+                time,
+                depends,
+                dependents: Vec::new(),
+                undo,
+            },
+        );
         self.data_len += bytes.len();
         self.transactions.push(hash);
-        //TODO wallet
-        //TODO rpc
-        debug!(self.logger, "Accepted {hash}");
-        Ok(fee)
+        Ok((scanner, effects))
     }
 
     fn check_fee(&self, size: u32, amount: Amount) -> Result<()> {
-        if amount >= self.settings.min_relay_fee_rate * (1 + size / 1000).into() {
+        if amount >= self.min_fee_rate() * (1 + size / 1000).into() {
             Ok(())
         } else {
-            Err(Error::invalid(format!("Too low fee {}", amount)))
+            Err(Error::in_future(format!("Too low fee {}", amount)))
+        }
+    }
+
+    fn rolling_fee_rate(&self, time: Milliseconds) -> Amount {
+        let (rate, since) = self.rolling_fee_rate;
+        if rate == Amount::ZERO {
+            return Amount::ZERO;
+        }
+        let halvings = (time - since).value() as f64 / ROLLING_FEE_HALFLIFE.value() as f64;
+        let decayed = (rate.value() as f64 / halvings.max(0.0).exp2()) as u64;
+        // negligible against relay fee
+        if decayed < self.settings.min_relay_fee_rate.value() / 2 {
+            Amount::ZERO
+        } else {
+            Amount::new(decayed)
+        }
+    }
+
+    /**
+     * Applies transaction on top of pool state, returns pool transactions that it depends on and
+     * what it has overwritten
+     */
+    fn apply(&mut self, tx: &Transaction, hash: Hash) -> Result<(Vec<Hash>, Undo)> {
+        let result = self.process_transaction_impl(tx, hash);
+        if result.is_err() {
+            return self
+                .undo_impl(result)
+                .map(|()| (Vec::new(), Undo::default()));
+        }
+        let mut depends = Vec::<Hash>::new();
+        let mut depend = |writer: Option<Hash>| {
+            if let Some(writer) = writer
+                && !depends.contains(&writer)
+            {
+                depends.push(writer);
+            }
+            writer
+        };
+        let mut undo = Undo::default();
+        for (key, account) in self.undo_accounts.drain() {
+            let writer = depend(self.account_writers.insert(key, hash));
+            undo.accounts.push((key, account, writer));
+        }
+        for (id, htlc) in self.undo_htlcs.drain() {
+            let writer = depend(self.htlc_writers.insert(id, hash));
+            undo.htlcs.push((id, htlc, writer));
+        }
+        for (id, multisig) in self.undo_multisigs.drain() {
+            let writer = depend(self.multisig_writers.insert(id, hash));
+            undo.multisigs.push((id, multisig, writer));
+        }
+        Ok((depends, undo))
+    }
+
+    fn link(&mut self, hash: Hash, depends: &[Hash]) {
+        for depend in depends {
+            self.map.get_mut(depend).unwrap().dependents.push(hash);
+        }
+    }
+
+    /**
+     * Takes transaction off the pool state, that it has to be the last writer of
+     */
+    fn unapply(&mut self, hash: Hash) {
+        let entry = self.map.remove(&hash).unwrap();
+        self.data_len -= entry.bytes.len();
+        for depend in entry.depends {
+            if let Some(depend) = self.map.get_mut(&depend) {
+                depend.dependents.retain(|dependent| *dependent != hash);
+            }
+        }
+        for (key, account, writer) in entry.undo.accounts {
+            match account {
+                Some(account) => self.accounts.insert(key, account),
+                None => self.accounts.remove(&key),
+            };
+            match writer {
+                Some(writer) => self.account_writers.insert(key, writer),
+                None => self.account_writers.remove(&key),
+            };
+        }
+        for (id, (insert, htlc), writer) in entry.undo.htlcs {
+            if insert {
+                self.htlcs.insert(id, htlc);
+            } else {
+                self.htlcs.remove(&id);
+            }
+            match writer {
+                Some(writer) => self.htlc_writers.insert(id, writer),
+                None => self.htlc_writers.remove(&id),
+            };
+        }
+        for (id, (insert, multisig), writer) in entry.undo.multisigs {
+            if insert {
+                self.multisigs.insert(id, multisig);
+            } else {
+                self.multisigs.remove(&id);
+            }
+            match writer {
+                Some(writer) => self.multisig_writers.insert(id, writer),
+                None => self.multisig_writers.remove(&id),
+            };
+        }
+    }
+
+    /**
+     * Next sequence number of account, counting its transactions in the pool
+     */
+    pub fn sequence(&self, key: PublicKey) -> u32 {
+        match self.accounts.get(&key) {
+            Some(account) => account.seq(),
+            None => self.coin_db.account(key).map_or(0, |account| account.seq()),
         }
     }
 
+    /**
+     * Balance of account, counting its transactions in the pool
+     */
+    pub fn balance(&self, key: PublicKey) -> Amount {
+        match self.accounts.get(&key) {
+            Some(account) => account.balance(),
+            None => self
+                .coin_db
+                .account(key)
+                .map_or(Amount::ZERO, |account| account.balance()),
+        }
+    }
+
+    /**
+     * Evicts lowest fee rate transactions with their dependents until the pool fits its size
+     */
+    fn trim(&mut self, time: Milliseconds, keep: Option<Hash>) {
+        let mut candidates = self
+            .map
+            .iter()
+            .filter(|(hash, _)| Some(**hash) != keep)
+            .map(|(&hash, entry)| (entry.fee_rate, hash))
+            .collect::<Vec<_>>();
+        candidates.sort_unstable_by_key(|(fee_rate, _)| *fee_rate);
+        let mut evicted = HashSet::<Hash>::new();
+        let mut evicted_len = 0;
+        let mut max_evicted_rate = Amount::ZERO;
+        for (fee_rate, hash) in candidates {
+            if self.data_len - evicted_len <= self.settings.tx_pool_size {
+                break;
+            }
+            if evicted.contains(&hash) {
+                continue;
+            }
+            let package = self.with_dependents(hash, &evicted);
+            if keep.is_some_and(|keep| package.contains(&keep)) {
+                continue;
+            }
+            max_evicted_rate = max(max_evicted_rate, fee_rate);
+            for hash in package {
+                evicted_len += self.map[&hash].bytes.len();
+                evicted.insert(hash);
+            }
+        }
+        if self.data_len - evicted_len > self.settings.tx_pool_size {
+            warn!(self.logger, "TxPool is full");
+        }
+        if evicted.is_empty() {
+            return;
+        }
+        let rate = max_evicted_rate + self.settings.min_relay_fee_rate;
+        if rate > self.rolling_fee_rate(time) {
+            self.rolling_fee_rate = (rate, time);
+        }
+        debug!(
+            self.logger,
+            "Evicted {} transactions, min fee rate {}",
+            evicted.len(),
+            self.min_fee_rate()
+        );
+        self.remove(&evicted);
+    }
+
+    fn expire(&mut self, time: Milliseconds) {
+        self.last_expiry = time;
+        let mut expired = HashSet::<Hash>::new();
+        for (&hash, entry) in self.map.iter() {
+            if time > entry.time + MAX_AGE && !expired.contains(&hash) {
+                let package = self.with_dependents(hash, &expired);
+                expired.extend(package);
+            }
+        }
+        if !expired.is_empty() {
+            info!(self.logger, "Expired {} transactions", expired.len());
+            self.remove(&expired);
+        }
+    }
+
+    /**
+     * Transaction and its dependents, except those of `closed` set that has its dependents
+     */
+    fn with_dependents(&self, hash: Hash, closed: &HashSet<Hash>) -> HashSet<Hash> {
+        let mut result = HashSet::<Hash>::new();
+        let mut stack = vec![hash];
+        while let Some(hash) = stack.pop() {
+            if !closed.contains(&hash) && result.insert(hash) {
+                stack.extend(self.map[&hash].dependents.iter().copied());
+            }
+        }
+        result
+    }
+
+    /**
+     * Removes transactions along with their dependents, leaving the rest applied
+     */
+    fn remove(&mut self, hashes: &HashSet<Hash>) {
+        let removed = self
+            .transactions
+            .iter()
+            .rev()
+            .copied()
+            .filter(|hash| hashes.contains(hash))
+            .collect::<Vec<Hash>>();
+        self.transactions.retain(|hash| !hashes.contains(hash));
+        // newest first, each one is on top of pool state then
+        for &hash in removed.iter() {
+            self.unapply(hash);
+        }
+        self.wallet_db.remove_unconfirmed(&removed);
+    }
+
+    /**
+     * Records transactions of wallets that have been added to `WalletDB`
+     */
+    pub fn wallet_added(&mut self) {
+        self.rebuild();
+    }
+
+    /**
+     * Reapplies remaining transactions on top of `CoinDB` state, dropping those that fail
+     */
+    fn rebuild(&mut self) {
+        self.accounts.clear();
+        self.htlcs.clear();
+        self.multisigs.clear();
+        self.account_writers.clear();
+        self.htlc_writers.clear();
+        self.multisig_writers.clear();
+        self.wallet_db.clear_unconfirmed();
+        for entry in self.map.values_mut() {
+            entry.dependents.clear();
+        }
+        let mut scanner = self.wallet_db.scanner();
+        for hash in take(&mut self.transactions) {
+            let entry = &self.map[&hash];
+            let time = to_seconds(entry.time);
+            let result = from_bytes::<Transaction>(&entry.bytes, false)
+                .map_err(Error::from)
+                .and_then(|tx| {
+                    let effects = scanner.transaction(&*self, &tx, hash, None, time);
+                    Ok((effects, self.apply(&tx, hash)?))
+                });
+            match result {
+                Ok((effects, (depends, undo))) => {
+                    self.link(hash, &depends);
+                    let entry = self.map.get_mut(&hash).unwrap();
+                    entry.depends = depends;
+                    entry.undo = undo;
+                    scanner.record(effects, &entry.bytes);
+                    self.transactions.push(hash);
+                }
+                Err(err) => {
+                    debug!(self.logger, "Dropped {hash}: {err}");
+                    let entry = self.map.remove(&hash).unwrap();
+                    self.data_len -= entry.bytes.len();
+                }
+            }
+        }
+        self.wallet_db.commit(&mut scanner, None);
+    }
+
     fn undo_impl(&mut self, result: Result<()>) -> Result<()> {
         if result.is_ok() {
             self.undo_accounts.clear();
@@ -188,6 +737,38 @@ impl TxPool {
     }
 }
 
+// without the undo bookkeeping of get_htlc and get_multisig
+impl Contracts for TxPool {
+    fn htlc(&self, id: HashTimeLockContractId) -> Option<HTLC> {
+        match self.htlcs.get(&id) {
+            Some(htlc) => htlc.clone(),
+            None => self.coin_db.htlc(id),
+        }
+    }
+
+    fn multisig(&self, id: MultiSignatureLockContractId) -> Option<Multisig> {
+        match self.multisigs.get(&id) {
+            Some(multisig) => multisig.clone(),
+            None => self.coin_db.multisig(id),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+struct Persistent {
+    time: Milliseconds,
+    bytes: Box<[u8]>,
+}
+
+fn fee_rate(fee: Amount, size: usize) -> Amount {
+    let rate = fee.value() as u128 * 1000 / size.max(1) as u128;
+    Amount::new(rate.try_into().unwrap_or(u64::MAX))
+}
+
+const fn to_seconds(time: Milliseconds) -> Seconds {
+    Seconds::new(time.value() / 1000)
+}
+
 impl CoinTx for TxPool {
     fn add_supply(&mut self, _amount: Amount) {}
 
@@ -0,0 +1,174 @@
+/*
+ * Copyright (c) 2026 Pavel Vasin
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Lesser General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::blockdb::BlockDB;
+use crate::coindb::CoinDB;
+use blacknet_kernel::blake2b::Hash;
+use blacknet_kernel::ed25519::PublicKey;
+use blacknet_log::{Logger, error, info};
+use blacknet_wallet::walletdb::{Error as WalletError, WalletDB};
+use core::cmp::min;
+
+const PROGRESS_INTERVAL: u32 = 10000;
+const BATCH_SIZE: u32 = 1000;
+
+/**
+ * Brings wallets up to the chain on startup. Synced block that has left the chain meanwhile
+ * is behind a fork, that is at most at the rolling checkpoint.
+ */
+pub(crate) fn synchronize(
+    logger: &Logger,
+    block_db: &BlockDB,
+    coin_db: &CoinDB,
+    wallet_db: &WalletDB,
+) {
+    for public_key in wallet_db.public_keys() {
+        if let Err(err) = synchronize_wallet(logger, block_db, coin_db, wallet_db, public_key) {
+            error!(logger, "Wallet {public_key:?} error: {err}");
+            wallet_db.detach(public_key);
+        }
+    }
+}
+
+fn synchronize_wallet(
+    logger: &Logger,
+    block_db: &BlockDB,
+    coin_db: &CoinDB,
+    wallet_db: &WalletDB,
+    public_key: PublicKey,
+) -> Result<(), WalletError> {
+    if wallet_db.synced(public_key)?.is_none() {
+        // wallet of older version sees blocks from now on
+        let state = coin_db.state();
+        return wallet_db.set_synced(public_key, state.block_hash(), state.height());
+    }
+    catch_up(logger, block_db, coin_db, wallet_db, public_key)
+}
+
+/**
+ * Replays blocks after the synced one up to the tip. Blocks are replayed in batches under the
+ * lock of BlockDB, and the wallet stays detached from connected blocks until it reaches the tip.
+ */
+fn catch_up(
+    logger: &Logger,
+    block_db: &BlockDB,
+    coin_db: &CoinDB,
+    wallet_db: &WalletDB,
+    public_key: PublicKey,
+) -> Result<(), WalletError> {
+    wallet_db.detach(public_key);
+    loop {
+        let _processing = block_db.lock();
+        let state = coin_db.state();
+        let (mut hash, mut height) =
+            synced_in_chain(logger, block_db, coin_db, wallet_db, public_key)?;
+        if hash == state.block_hash() {
+            wallet_db.attach(public_key);
+            return Ok(());
+        }
+        let mut scanner = wallet_db.wallet_scanner(public_key)?;
+        for _ in 0..BATCH_SIZE {
+            if hash == state.block_hash() {
+                break;
+            }
+            hash = block_db.index(hash).expect("consistent block index").next();
+            height += 1;
+            let index = block_db.index(hash).expect("consistent block index");
+            let (block, _) = block_db.get(hash).expect("consistent block db");
+            scanner.block(coin_db, hash, height, &block, index.generated());
+            if let Some((_, err)) = scanner.commit(Some((hash, height)), false).pop() {
+                return Err(err);
+            }
+            if height.is_multiple_of(PROGRESS_INTERVAL) {
+                info!(
+                    logger,
+                    "Catching up wallet {public_key:?} at height {height}"
+                );
+            }
+        }
+        // next batch starts at the synced block
+        if let Some((_, err)) = scanner.commit(Some((hash, height)), true).pop() {
+            return Err(err);
+        }
+    }
+}
+
+/**
+ * Synced block of wallet. The one that has left the chain is behind a fork, that is at most at
+ * the rolling checkpoint, and the wallet is rewound there.
+ */
+fn synced_in_chain(
+    logger: &Logger,
+    block_db: &BlockDB,
+    coin_db: &CoinDB,
+    wallet_db: &WalletDB,
+    public_key: PublicKey,
+) -> Result<(Hash, u32), WalletError> {
+    let state = coin_db.state();
+    let Some((hash, height)) = wallet_db.synced(public_key)? else {
+        return Ok((state.block_hash(), state.height()));
+    };
+    if block_db
+        .index(hash)
+        .is_some_and(|index| index.height() == height)
+    {
+        return Ok((hash, height));
+    }
+    let checkpoint = block_db
+        .index(state.rolling_checkpoint())
+        .map_or(0, |index| index.height());
+    let height = min(height, checkpoint + 1).min(state.height() + 1).max(1) - 1;
+    info!(
+        logger,
+        "Wallet {public_key:?} synced block {hash} left the chain, rewinding to height {height}"
+    );
+    let hash = block_db
+        .hash(height, &state)
+        .expect("consistent block index");
+    wallet_db.rewind(public_key, hash, height)?;
+    Ok((hash, height))
+}
+
+/**
+ * Forgets blocks from `height` on and replays them into the wallet
+ */
+pub(crate) fn rescan(
+    logger: &Logger,
+    block_db: &BlockDB,
+    coin_db: &CoinDB,
+    wallet_db: &WalletDB,
+    public_key: PublicKey,
+    height: u32,
+) -> Result<(), WalletError> {
+    {
+        let _processing = block_db.lock();
+        let state = coin_db.state();
+        // genesis has no block body
+        let height = min(height.max(1) - 1, state.height());
+        let hash = block_db
+            .hash(height, &state)
+            .expect("consistent block index");
+        info!(
+            logger,
+            "Rescanning wallet {public_key:?} from height {}",
+            height + 1
+        );
+        wallet_db.detach(public_key);
+        wallet_db.rewind(public_key, hash, height)?;
+    }
+    catch_up(logger, block_db, coin_db, wallet_db, public_key)
+}
@@ -0,0 +1,113 @@
+/*
+ * Copyright (c) 2026 Pavel Vasin
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Lesser General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use blacknet_compat::{Mode, XDGDirectories};
+use blacknet_kernel::amount::Amount;
+use blacknet_kernel::blake2b::Hash;
+use blacknet_kernel::block::Block;
+use blacknet_kernel::ed25519::PublicKey;
+use blacknet_kernel::transaction::{PaymentId, Transaction, Transfer, TxKind};
+use blacknet_network::addressindex::{AddressIndex, Key};
+use blacknet_network::fjall::Fjall;
+use blacknet_network::genesis;
+use blacknet_network::settings::Settings;
+use blacknet_serialization::format::to_bytes;
+use blacknet_time::Seconds;
+use std::sync::Arc;
+
+fn transfer(from: PublicKey, to: PublicKey) -> (Hash, Box<[u8]>) {
+    let data = to_bytes(&Transfer::new(Amount::new(1), to, PaymentId::plain(""))).unwrap();
+    let tx = Transaction::new(
+        from,
+        0,
+        Hash::ZERO,
+        Amount::new(1),
+        TxKind::Transfer,
+        data.into(),
+    );
+    let bytes = to_bytes(&tx).unwrap();
+    (Transaction::compute_hash(&bytes).unwrap(), bytes.into())
+}
+
+#[test]
+fn key() {
+    let public_key = PublicKey::from([7; 32]);
+    let key = Key::new(public_key, 0x01020304, 5, 6);
+    let bytes = key.as_ref();
+    assert_eq!(bytes.len(), 44);
+    assert_eq!(bytes[..32], [7; 32]);
+    assert_eq!(bytes[32..], [1, 2, 3, 4, 0, 0, 0, 5, 0, 0, 0, 6]);
+    let key = Key::try_from(bytes).unwrap();
+    assert_eq!(key.height(), 0x01020304);
+    assert_eq!(key.position(), 5);
+    assert_eq!(key.data_index(), 6);
+    assert!(Key::try_from(&bytes[..43]).is_err());
+}
+
+#[test]
+fn address_index() {
+    let dirs = XDGDirectories::temporary("addressindex").unwrap();
+    let settings = Arc::new(Settings::default(&Mode::regtest()));
+    let fjall = Fjall::open(&dirs, &settings).unwrap();
+    let address_index = AddressIndex::new(&fjall).unwrap();
+
+    let alice = PublicKey::from([1; 32]);
+    let bob = PublicKey::from([2; 32]);
+    let (first, first_bytes) = transfer(alice, bob);
+    let (second, second_bytes) = transfer(bob, alice);
+    let block = Block::with_all(
+        0,
+        genesis::hash(),
+        Seconds::new(1),
+        alice,
+        Hash::ZERO,
+        Default::default(),
+        [first_bytes, second_bytes].into(),
+    );
+    let hash = Hash::from([9; 32]);
+
+    let mut batch = fjall.create_write_batch();
+    address_index.connect(&mut batch, hash, 1, &block);
+    batch.commit().unwrap();
+    assert!(address_index.is_synchronized(hash));
+    let history = address_index.history(alice, 0, 10);
+    assert_eq!(
+        history
+            .iter()
+            .map(|(key, tx)| (key.height(), key.position(), key.data_index(), *tx))
+            .collect::<Vec<_>>(),
+        [(1, 1, 0, second), (1, 0, 0, first)]
+    );
+    assert_eq!(address_index.history(alice, 1, 10).len(), 1);
+    assert_eq!(address_index.history(bob, 0, 10).len(), 2);
+
+    let mut batch = fjall.create_write_batch();
+    address_index.disconnect(&mut batch, 1, &block);
+    batch.commit().unwrap();
+    assert!(address_index.is_synchronized(genesis::hash()));
+    assert!(address_index.history(alice, 0, 10).is_empty());
+    assert!(address_index.history(bob, 0, 10).is_empty());
+
+    let mut batch = fjall.create_write_batch();
+    address_index.connect(&mut batch, hash, 1, &block);
+    batch.commit().unwrap();
+    let mut batch = fjall.create_write_batch();
+    address_index.clear(&mut batch);
+    batch.commit().unwrap();
+    assert!(address_index.is_synchronized(genesis::hash()));
+    assert!(address_index.history(bob, 0, 10).is_empty());
+}
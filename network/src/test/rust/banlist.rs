@@ -0,0 +1,72 @@
+/*
+ * Copyright (c) 2026 Pavel Vasin
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Lesser General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use blacknet_compat::{Mode, XDGDirectories};
+use blacknet_log::{LogManager, Strategy};
+use blacknet_network::banlist::{BanList, DEFAULT_BAN_TIME};
+use blacknet_network::endpoint::Endpoint;
+use blacknet_network::settings::Settings;
+use blacknet_time::Milliseconds;
+use std::sync::Arc;
+
+#[test]
+fn ban_list() {
+    let dirs = XDGDirectories::temporary("banlist").unwrap();
+    let log_manager = LogManager::new(Strategy::UnitTest, dirs.data()).unwrap();
+    let settings = Arc::new(Settings::default(&Mode::regtest()));
+
+    let host = Endpoint::parse("192.0.2.1", 28453).unwrap();
+    let neighbour = Endpoint::parse("192.0.2.2", 28453).unwrap();
+    let stranger = Endpoint::parse("198.51.100.1", 28453).unwrap();
+    let onion = Endpoint::parse(
+        "pg6mmjiyjmcrsslvykfwnntlaru7p5svn6y2ymmju6nubxndf4pscryd.onion",
+        28453,
+    )
+    .unwrap();
+
+    let ban_list = BanList::new(&dirs, &log_manager, settings.clone()).unwrap();
+    assert!(ban_list.is_empty());
+    assert!(ban_list.ban(host, false, DEFAULT_BAN_TIME, "test".to_owned()));
+    assert!(ban_list.is_banned(host));
+    assert!(ban_list.is_banned(host.with_port(1)));
+    assert!(!ban_list.is_banned(neighbour));
+
+    assert!(!ban_list.ban(onion, true, DEFAULT_BAN_TIME, "test".to_owned()));
+    assert!(ban_list.ban(neighbour, true, Milliseconds::MAX, "test".to_owned()));
+    assert!(ban_list.is_banned(neighbour));
+    assert!(!ban_list.is_banned(stranger));
+    assert_eq!(ban_list.len(), 2);
+
+    assert!(ban_list.ban(stranger, false, Milliseconds::ZERO, "test".to_owned()));
+    assert!(!ban_list.is_banned(stranger));
+    assert_eq!(
+        ban_list.map(|endpoint, subnet, _| (endpoint, subnet)).len(),
+        2
+    );
+
+    let ban_list = BanList::new(&dirs, &log_manager, settings.clone()).unwrap();
+    assert_eq!(ban_list.len(), 2);
+    assert!(ban_list.is_banned(host));
+    assert!(!ban_list.unban(neighbour, false));
+    assert!(ban_list.unban(neighbour, true));
+    assert!(!ban_list.is_banned(neighbour));
+    assert!(ban_list.unban(host, false));
+    assert!(ban_list.is_empty());
+
+    let ban_list = BanList::new(&dirs, &log_manager, settings).unwrap();
+    assert!(ban_list.is_empty());
+}
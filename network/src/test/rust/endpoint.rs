@@ -17,6 +17,7 @@
 
 use blacknet_network::endpoint::Endpoint;
 use blacknet_serialization::format::{from_bytes, to_bytes, to_size};
+use core::net::SocketAddr;
 
 #[test]
 fn ipv4() {
@@ -109,6 +110,46 @@ fn compare() {
     assert_ne!(d, a);
 }
 
+#[test]
+fn subnet() {
+    let a = Endpoint::parse("203.0.113.1", 28453).unwrap();
+    let b = Endpoint::parse("203.0.200.2", 1024).unwrap();
+    let c = Endpoint::parse("203.1.113.1", 28453).unwrap();
+    let d = Endpoint::parse("2001:db8::1", 28453).unwrap();
+    let e = Endpoint::parse("2001:db8:ffff::1", 28453).unwrap();
+    let f = Endpoint::parse(
+        "mzgt4svgc72euhvkpfdow7aiiivziqwhsl2fdzgiwkqeronnjjtq.b32.i2p",
+        0,
+    )
+    .unwrap();
+
+    assert_eq!(a.subnet(), b.subnet());
+    assert_ne!(a.subnet(), c.subnet());
+    assert_eq!(d.subnet(), e.subnet());
+    assert_ne!(a.subnet(), d.subnet());
+    assert_eq!(f.subnet(), None);
+}
+
+#[test]
+fn host() {
+    let a = Endpoint::parse("203.0.113.1", 28453).unwrap();
+    let b = Endpoint::parse("203.0.113.1", 50123).unwrap();
+    assert_eq!(a.host(), b.host());
+    assert_eq!(a.host().port(), 0);
+    assert_ne!(a.host(), a.subnet().unwrap());
+}
+
+#[test]
+fn socket_addr() {
+    let addr = "[::ffff:203.0.113.1]:28453".parse::<SocketAddr>().unwrap();
+    assert_eq!(
+        Endpoint::from(addr),
+        Endpoint::parse("203.0.113.1", 28453).unwrap()
+    );
+    let addr = "[2001:db8::1]:28453".parse::<SocketAddr>().unwrap();
+    assert_eq!(Endpoint::from(addr).to_rust(), Some(addr));
+}
+
 #[test]
 fn serialization() {
     let endpoint = Endpoint::parse("127.0.0.4", 258).unwrap();
@@ -0,0 +1,89 @@
+/*
+ * Copyright (c) 2026 Pavel Vasin
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Lesser General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use blacknet_compat::{Mode, XDGDirectories};
+use blacknet_kernel::amount::Amount;
+use blacknet_kernel::blake2b::Hash;
+use blacknet_kernel::block::Block;
+use blacknet_kernel::ed25519::PublicKey;
+use blacknet_kernel::transaction::{Transaction, TxKind};
+use blacknet_network::fjall::Fjall;
+use blacknet_network::genesis;
+use blacknet_network::settings::Settings;
+use blacknet_network::txindex::TxIndex;
+use blacknet_serialization::format::to_bytes;
+use blacknet_time::Seconds;
+use std::sync::Arc;
+
+fn tx(seq: u32) -> (Hash, Box<[u8]>) {
+    let tx = Transaction::new(
+        PublicKey::from([1; 32]),
+        seq,
+        Hash::ZERO,
+        Amount::new(1),
+        TxKind::Burn,
+        Default::default(),
+    );
+    let bytes = to_bytes(&tx).unwrap();
+    (Transaction::compute_hash(&bytes).unwrap(), bytes.into())
+}
+
+#[test]
+fn tx_index() {
+    let dirs = XDGDirectories::temporary("txindex").unwrap();
+    let settings = Arc::new(Settings::default(&Mode::regtest()));
+    let fjall = Fjall::open(&dirs, &settings).unwrap();
+    let tx_index = TxIndex::new(&fjall).unwrap();
+
+    let (first, first_bytes) = tx(0);
+    let (second, second_bytes) = tx(1);
+    let block = Block::with_all(
+        0,
+        genesis::hash(),
+        Seconds::new(1),
+        PublicKey::default(),
+        Hash::ZERO,
+        Default::default(),
+        [first_bytes, second_bytes].into(),
+    );
+    let hash = Hash::from([9; 32]);
+
+    let mut batch = fjall.create_write_batch();
+    tx_index.connect(&mut batch, hash, &block);
+    batch.commit().unwrap();
+    assert!(tx_index.is_synchronized(hash));
+    let location = tx_index.get(second).unwrap();
+    assert_eq!(location.block(), hash);
+    assert_eq!(location.position(), 1);
+    assert_eq!(tx_index.get(first).unwrap().position(), 0);
+
+    let mut batch = fjall.create_write_batch();
+    tx_index.disconnect(&mut batch, &block);
+    batch.commit().unwrap();
+    assert!(tx_index.is_synchronized(genesis::hash()));
+    assert!(tx_index.get(first).is_none());
+    assert!(tx_index.get(second).is_none());
+
+    let mut batch = fjall.create_write_batch();
+    tx_index.connect(&mut batch, hash, &block);
+    batch.commit().unwrap();
+    let mut batch = fjall.create_write_batch();
+    tx_index.clear(&mut batch);
+    batch.commit().unwrap();
+    assert!(tx_index.is_synchronized(genesis::hash()));
+    assert!(tx_index.get(first).is_none());
+}
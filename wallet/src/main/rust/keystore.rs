@@ -0,0 +1,165 @@
+/*
+ * Copyright (c) 2026 Pavel Vasin
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Lesser General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::walletdb::Error;
+use argon2::{Algorithm, Argon2, Params, Version};
+use blacknet_compat::getentropy;
+use blacknet_crypto::symmetric::{Blake2b256, ChaCha20};
+use blacknet_kernel::ed25519::SecretKey;
+use zeroize::Zeroizing;
+
+pub const SALT_SIZE: usize = 16;
+pub const NONCE_SIZE: usize = 12;
+pub const TAG_SIZE: usize = 32;
+/**
+ * Memory cost of Argon2id in KiB
+ */
+pub const DEFAULT_MEMORY: u32 = 64 * 1024;
+pub const DEFAULT_ITERATIONS: u32 = 3;
+const MAC_PERSONALIZATION: [u8; 16] = *b"Blacknet MAC\0\0\0\0";
+
+/**
+ * Secret key encrypted with ChaCha20 and authenticated with keyed BLAKE2b (encrypt-then-MAC).
+ * Both keys are derived from password by Argon2id; its costs are stored along, so that they
+ * can be raised for new keys without breaking the old ones.
+ */
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EncryptedKey {
+    salt: [u8; SALT_SIZE],
+    memory: u32,
+    iterations: u32,
+    nonce: [u8; NONCE_SIZE],
+    cipher_text: [u8; 32],
+    tag: [u8; TAG_SIZE],
+}
+
+impl EncryptedKey {
+    pub const fn new(
+        salt: [u8; SALT_SIZE],
+        memory: u32,
+        iterations: u32,
+        nonce: [u8; NONCE_SIZE],
+        cipher_text: [u8; 32],
+        tag: [u8; TAG_SIZE],
+    ) -> Self {
+        Self {
+            salt,
+            memory,
+            iterations,
+            nonce,
+            cipher_text,
+            tag,
+        }
+    }
+
+    pub fn encrypt(
+        secret_key: &SecretKey,
+        password: &str,
+        memory: u32,
+        iterations: u32,
+    ) -> Result<Self, Error> {
+        let mut salt = [0u8; SALT_SIZE];
+        getentropy(&mut salt).map_err(Error::Entropy)?;
+        let mut nonce = [0u8; NONCE_SIZE];
+        getentropy(&mut nonce).map_err(Error::Entropy)?;
+
+        let keys = derive(password, &salt, memory, iterations)?;
+        let (cipher_key, mac_key) = split(&keys);
+        let mut cipher_text = [0u8; 32];
+        ChaCha20::new(cipher_key, &nonce).encrypt(&mut cipher_text, secret_key.as_ref());
+        let tag = authenticate(mac_key, &nonce, &cipher_text);
+
+        Ok(Self {
+            salt,
+            memory,
+            iterations,
+            nonce,
+            cipher_text,
+            tag,
+        })
+    }
+
+    pub fn decrypt(&self, password: &str) -> Result<Zeroizing<SecretKey>, Error> {
+        let keys = derive(password, &self.salt, self.memory, self.iterations)?;
+        let (cipher_key, mac_key) = split(&keys);
+        let tag = authenticate(mac_key, &self.nonce, &self.cipher_text);
+        if !constant_time_eq(&tag, &self.tag) {
+            return Err(Error::WrongPassword);
+        }
+        let mut plain_text = Zeroizing::new([0u8; 32]);
+        ChaCha20::new(cipher_key, &self.nonce).decrypt(&mut *plain_text, &self.cipher_text);
+        Ok(Zeroizing::new(SecretKey::from(*plain_text)))
+    }
+
+    pub const fn salt(&self) -> &[u8; SALT_SIZE] {
+        &self.salt
+    }
+
+    pub const fn memory(&self) -> u32 {
+        self.memory
+    }
+
+    pub const fn iterations(&self) -> u32 {
+        self.iterations
+    }
+
+    pub const fn nonce(&self) -> &[u8; NONCE_SIZE] {
+        &self.nonce
+    }
+
+    pub const fn cipher_text(&self) -> &[u8; 32] {
+        &self.cipher_text
+    }
+
+    pub const fn tag(&self) -> &[u8; TAG_SIZE] {
+        &self.tag
+    }
+}
+
+fn derive(
+    password: &str,
+    salt: &[u8; SALT_SIZE],
+    memory: u32,
+    iterations: u32,
+) -> Result<Zeroizing<[u8; 64]>, Error> {
+    let params = Params::new(memory, iterations, 1, Some(64)).map_err(Error::Kdf)?;
+    let mut keys = Zeroizing::new([0u8; 64]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(password.as_bytes(), salt, &mut *keys)
+        .map_err(Error::Kdf)?;
+    Ok(keys)
+}
+
+fn split(keys: &[u8; 64]) -> (&[u8; 32], &[u8; 32]) {
+    let (cipher_key, mac_key) = keys.split_at(32);
+    (cipher_key.try_into().unwrap(), mac_key.try_into().unwrap())
+}
+
+fn authenticate(
+    mac_key: &[u8; 32],
+    nonce: &[u8; NONCE_SIZE],
+    cipher_text: &[u8],
+) -> [u8; TAG_SIZE] {
+    let mut hasher = Blake2b256::with_key(mac_key, MAC_PERSONALIZATION);
+    hasher.update(nonce);
+    hasher.update(cipher_text);
+    hasher.finalize()
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
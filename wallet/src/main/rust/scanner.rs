@@ -0,0 +1,438 @@
+/*
+ * Copyright (c) 2026 Pavel Vasin
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Lesser General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::wallet::{Changes, Contract, HistoryEntry, Wallet};
+use crate::walletdb::Error;
+use blacknet_kernel::account::Lease as OutLease;
+use blacknet_kernel::amount::Amount;
+use blacknet_kernel::blake2b::Hash;
+use blacknet_kernel::block::Block;
+use blacknet_kernel::ed25519::PublicKey;
+use blacknet_kernel::htlc::HTLC;
+use blacknet_kernel::multisig::{Deposit, Multisig};
+use blacknet_kernel::transaction::{
+    Batch, Burn, CancelLease, ClaimHTLC, CreateHTLC, CreateMultisig, HashTimeLockContractId, Lease,
+    MultiSignatureLockContractId, RefundHTLC, SpendMultisig, Transaction, Transfer, TxKind,
+    WithdrawFromLease, htlc_id, multisig_id,
+};
+use blacknet_serialization::format::from_bytes;
+use blacknet_time::Seconds;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/**
+ * Contracts as they are before the scanned transaction
+ */
+pub trait Contracts {
+    fn htlc(&self, id: HashTimeLockContractId) -> Option<HTLC>;
+    fn multisig(&self, id: MultiSignatureLockContractId) -> Option<Multisig>;
+}
+
+/**
+ * Derives history and contracts of wallets from transactions, looking only at the keys that
+ * a transaction names and at the parties of contracts that it closes. Payouts of contracts that
+ * are known neither to `Contracts` nor to the wallets are missed.
+ */
+pub struct Scanner {
+    pub(crate) wallets: HashMap<PublicKey, Arc<Wallet>>,
+    pub(crate) changes: HashMap<PublicKey, Changes>,
+    // opened since creation of scanner
+    htlcs: HashMap<HashTimeLockContractId, HTLC>,
+    multisigs: HashMap<MultiSignatureLockContractId, Multisig>,
+}
+
+impl Scanner {
+    pub fn new(wallets: HashMap<PublicKey, Arc<Wallet>>) -> Self {
+        Self {
+            wallets,
+            changes: HashMap::new(),
+            htlcs: HashMap::new(),
+            multisigs: HashMap::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.wallets.is_empty()
+    }
+
+    /**
+     * Effects of a transaction that is about to be applied, `height` is none for TxPool
+     */
+    pub fn transaction(
+        &self,
+        contracts: &impl Contracts,
+        tx: &Transaction,
+        hash: Hash,
+        height: Option<u32>,
+        time: Seconds,
+    ) -> Effects {
+        let mut effects = Effects {
+            hash,
+            kind: tx.kind(),
+            height,
+            time,
+            deltas: Vec::new(),
+            contracts: Vec::new(),
+        };
+        if !self.is_empty() {
+            self.spend(&mut effects, tx.from(), tx.fee());
+            self.data(contracts, tx, tx.kind(), tx.data_bytes(), 0, &mut effects);
+        }
+        effects
+    }
+
+    /**
+     * Keeps effects of the transaction that has been applied
+     */
+    pub fn record(&mut self, effects: Effects, bytes: &[u8]) {
+        for (key, contract) in effects.contracts {
+            match &contract {
+                Contract::CreateHTLC(id, htlc) => {
+                    self.htlcs.insert(*id, htlc.clone());
+                }
+                Contract::CreateMultisig(id, multisig) => {
+                    self.multisigs.insert(*id, multisig.clone());
+                }
+                _ => (),
+            }
+            self.changes
+                .entry(key)
+                .or_default()
+                .contracts
+                .push(contract);
+        }
+        for (key, delta) in effects.deltas {
+            let entry = HistoryEntry::new(
+                effects.hash,
+                effects.kind as u8,
+                effects.height,
+                effects.time,
+                delta,
+            );
+            self.changes
+                .entry(key)
+                .or_default()
+                .entries
+                .push((entry, Some(bytes.into())));
+        }
+    }
+
+    pub fn generated(&mut self, hash: Hash, height: u32, block: &Block, generated: Amount) {
+        if self.wallets.contains_key(&block.generator()) {
+            let entry = HistoryEntry::new(
+                hash,
+                TxKind::Generated as u8,
+                Some(height),
+                block.time(),
+                generated.value() as i64,
+            );
+            self.changes
+                .entry(block.generator())
+                .or_default()
+                .entries
+                .push((entry, None));
+        }
+    }
+
+    /**
+     * Replays a block of the chain
+     */
+    pub fn block(
+        &mut self,
+        contracts: &impl Contracts,
+        hash: Hash,
+        height: u32,
+        block: &Block,
+        generated: Amount,
+    ) {
+        for bytes in block.raw_transactions() {
+            let (Some(tx), Some(tx_hash)) = (
+                decode::<Transaction>(bytes),
+                Transaction::compute_hash(bytes),
+            ) else {
+                continue;
+            };
+            let effects = self.transaction(contracts, &tx, tx_hash, Some(height), block.time());
+            self.record(effects, bytes);
+        }
+        self.generated(hash, height, block, generated);
+    }
+
+    /**
+     * Writes kept changes, moving wallets that have some to `synced`. Untouched wallets are moved
+     * too if `all` is set, otherwise they lag behind, that only costs a longer catch up.
+     */
+    pub fn commit(&mut self, synced: Option<(Hash, u32)>, all: bool) -> Vec<(PublicKey, Error)> {
+        let mut errors = Vec::new();
+        for (key, wallet) in self.wallets.iter() {
+            let changes = self.changes.remove(key).unwrap_or_default();
+            if changes.is_empty() && !(all && synced.is_some()) {
+                continue;
+            }
+            if let Err(err) = wallet.commit(&changes, synced) {
+                errors.push((*key, err));
+            }
+        }
+        errors
+    }
+
+    fn data(
+        &self,
+        contracts: &impl Contracts,
+        tx: &Transaction,
+        kind: TxKind,
+        bytes: &[u8],
+        data_index: u32,
+        effects: &mut Effects,
+    ) {
+        match kind {
+            TxKind::Transfer => {
+                let Some(data) = decode::<Transfer>(bytes) else {
+                    return;
+                };
+                self.spend(effects, tx.from(), data.amount());
+                self.receive(effects, data.to(), data.amount());
+            }
+            TxKind::Burn => {
+                let Some(data) = decode::<Burn>(bytes) else {
+                    return;
+                };
+                self.spend(effects, tx.from(), data.amount());
+            }
+            TxKind::Lease => {
+                let Some(data) = decode::<Lease>(bytes) else {
+                    return;
+                };
+                self.spend(effects, tx.from(), data.amount());
+                self.involve(effects, data.to());
+                if let Some(height) = effects.height {
+                    let lease = OutLease::new(data.to(), height, data.amount());
+                    self.contract(effects, tx.from(), Contract::Lease(lease));
+                }
+            }
+            TxKind::CancelLease => {
+                let Some(data) = decode::<CancelLease>(bytes) else {
+                    return;
+                };
+                self.receive(effects, tx.from(), data.amount());
+                self.involve(effects, data.to());
+                let lease = OutLease::new(data.to(), data.height(), data.amount());
+                self.contract(effects, tx.from(), Contract::CancelLease(lease));
+            }
+            TxKind::WithdrawFromLease => {
+                let Some(data) = decode::<WithdrawFromLease>(bytes) else {
+                    return;
+                };
+                self.receive(effects, tx.from(), data.withdraw());
+                self.involve(effects, data.to());
+                let lease = OutLease::new(data.to(), data.height(), data.amount());
+                let contract = Contract::WithdrawFromLease(lease, data.withdraw());
+                self.contract(effects, tx.from(), contract);
+            }
+            TxKind::CreateHTLC => {
+                let Some(data) = decode::<CreateHTLC>(bytes) else {
+                    return;
+                };
+                self.spend(effects, tx.from(), data.amount());
+                self.involve(effects, data.to());
+                if let Some(height) = effects.height {
+                    let id = htlc_id(effects.hash, data_index);
+                    let htlc = HTLC {
+                        height,
+                        time: effects.time,
+                        amount: data.amount(),
+                        from: tx.from(),
+                        to: data.to(),
+                        time_lock: data.time_lock().clone(),
+                        hash_lock: data.hash_lock().clone(),
+                    };
+                    for party in parties([tx.from(), data.to()]) {
+                        self.contract(effects, party, Contract::CreateHTLC(id, htlc.clone()));
+                    }
+                }
+            }
+            TxKind::ClaimHTLC => {
+                let Some(data) = decode::<ClaimHTLC>(bytes) else {
+                    return;
+                };
+                self.close_htlc(contracts, tx, data.id(), effects);
+            }
+            TxKind::RefundHTLC => {
+                let Some(data) = decode::<RefundHTLC>(bytes) else {
+                    return;
+                };
+                self.close_htlc(contracts, tx, data.id(), effects);
+            }
+            TxKind::CreateMultisig => {
+                let Some(data) = decode::<CreateMultisig>(bytes) else {
+                    return;
+                };
+                for deposit in data.deposits() {
+                    self.spend(effects, deposit.from(), deposit.amount());
+                }
+                if effects.height.is_some() {
+                    let id = multisig_id(effects.hash, data_index);
+                    let deposits = data.deposits().iter();
+                    let multisig = Multisig::new(
+                        data.n(),
+                        deposits
+                            .clone()
+                            .map(|deposit| Deposit::new(deposit.from(), deposit.amount()))
+                            .collect(),
+                    );
+                    for party in parties(deposits.map(|deposit| deposit.from())) {
+                        let contract = Contract::CreateMultisig(id, multisig.clone());
+                        self.contract(effects, party, contract);
+                    }
+                }
+            }
+            TxKind::SpendMultisig => {
+                let Some(data) = decode::<SpendMultisig>(bytes) else {
+                    return;
+                };
+                let Some(multisig) = self.multisig(contracts, data.id()) else {
+                    return;
+                };
+                for (deposit, &amount) in multisig.deposits().iter().zip(data.amounts()) {
+                    self.receive(effects, deposit.from(), amount);
+                }
+                let deposits = multisig.deposits().iter();
+                for party in parties(deposits.map(|deposit| deposit.from())) {
+                    self.contract(effects, party, Contract::SpendMultisig(data.id()));
+                }
+            }
+            TxKind::Batch if data_index == 0 => {
+                let Some(data) = decode::<Batch>(bytes) else {
+                    return;
+                };
+                for (index, batchee) in data.multi_data().iter().enumerate() {
+                    self.data(
+                        contracts,
+                        tx,
+                        batchee.kind(),
+                        batchee.data_bytes(),
+                        (index + 1) as u32,
+                        effects,
+                    );
+                }
+            }
+            _ => (),
+        }
+    }
+
+    // sender of claim or refund is the one who is paid
+    fn close_htlc(
+        &self,
+        contracts: &impl Contracts,
+        tx: &Transaction,
+        id: HashTimeLockContractId,
+        effects: &mut Effects,
+    ) {
+        if let Some(htlc) = self.htlc(contracts, id) {
+            self.receive(effects, tx.from(), htlc.amount);
+            for party in parties([htlc.from, htlc.to]) {
+                self.contract(effects, party, Contract::CloseHTLC(id));
+            }
+        }
+    }
+
+    fn htlc(&self, contracts: &impl Contracts, id: HashTimeLockContractId) -> Option<HTLC> {
+        self.htlcs
+            .get(&id)
+            .cloned()
+            .or_else(|| contracts.htlc(id))
+            .or_else(|| {
+                self.wallets
+                    .values()
+                    .find_map(|wallet| wallet.htlc(id).ok().flatten())
+            })
+    }
+
+    fn multisig(
+        &self,
+        contracts: &impl Contracts,
+        id: MultiSignatureLockContractId,
+    ) -> Option<Multisig> {
+        self.multisigs
+            .get(&id)
+            .cloned()
+            .or_else(|| contracts.multisig(id))
+            .or_else(|| {
+                self.wallets
+                    .values()
+                    .find_map(|wallet| wallet.multisig(id).ok().flatten())
+            })
+    }
+
+    fn spend(&self, effects: &mut Effects, key: PublicKey, amount: Amount) {
+        self.change(effects, key, -(amount.value() as i64));
+    }
+
+    fn receive(&self, effects: &mut Effects, key: PublicKey, amount: Amount) {
+        self.change(effects, key, amount.value() as i64);
+    }
+
+    fn involve(&self, effects: &mut Effects, key: PublicKey) {
+        self.change(effects, key, 0);
+    }
+
+    fn change(&self, effects: &mut Effects, key: PublicKey, delta: i64) {
+        if !self.wallets.contains_key(&key) {
+            return;
+        }
+        match effects.deltas.iter_mut().find(|(other, _)| *other == key) {
+            Some((_, sum)) => *sum += delta,
+            None => effects.deltas.push((key, delta)),
+        }
+    }
+
+    // history of TxPool has no contracts
+    fn contract(&self, effects: &mut Effects, key: PublicKey, contract: Contract) {
+        if effects.height.is_some() && self.wallets.contains_key(&key) {
+            self.involve(effects, key);
+            effects.contracts.push((key, contract));
+        }
+    }
+}
+
+/**
+ * Wallets involved in a transaction, with their balance changes and contracts
+ */
+pub struct Effects {
+    hash: Hash,
+    kind: TxKind,
+    height: Option<u32>,
+    time: Seconds,
+    deltas: Vec<(PublicKey, i64)>,
+    contracts: Vec<(PublicKey, Contract)>,
+}
+
+fn parties(keys: impl IntoIterator<Item = PublicKey>) -> Vec<PublicKey> {
+    let mut parties = Vec::<PublicKey>::new();
+    for key in keys {
+        if !parties.contains(&key) {
+            parties.push(key);
+        }
+    }
+    parties
+}
+
+// not yet validated in TxPool
+fn decode<'a, T: Deserialize<'a>>(bytes: &'a [u8]) -> Option<T> {
+    from_bytes::<T>(bytes, false).ok()
+}
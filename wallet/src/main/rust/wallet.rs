@@ -15,21 +15,42 @@
  * along with this program. If not, see <https://www.gnu.org/licenses/>.
  */
 
+use crate::keystore::EncryptedKey;
 use crate::walletdb::Error;
 use blacknet_compat::Mode;
 use blacknet_kernel::account::Lease;
 use blacknet_kernel::amount::Amount;
 use blacknet_kernel::blake2b::Hash;
-use blacknet_kernel::ed25519::PublicKey;
+use blacknet_kernel::ed25519::{PublicKey, SecretKey, to_public_key};
+use blacknet_kernel::htlc::HTLC;
+use blacknet_kernel::multisig::Multisig;
 use blacknet_kernel::transaction::{HashTimeLockContractId, MultiSignatureLockContractId};
+use blacknet_serialization::format::{from_bytes, to_bytes};
 use blacknet_time::{Seconds, SystemClock};
-use rusqlite::{Connection, OpenFlags};
+use core::fmt;
+use rusqlite::{Connection, Error as SqliteError, OpenFlags, OptionalExtension};
 use std::path::Path;
 use std::sync::Mutex;
+use zeroize::Zeroizing;
+
+const SCHEMA_VERSION: u32 = 2;
+pub const MAX_UNLOCK_TIMEOUT: Seconds = Seconds::with_days(365);
 
 #[derive(Debug)]
 pub struct Wallet {
     connection: Mutex<Connection>,
+    unlocked: Mutex<Option<Unlocked>>,
+}
+
+struct Unlocked {
+    secret_key: Zeroizing<SecretKey>,
+    until: Seconds,
+}
+
+impl fmt::Debug for Unlocked {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unlocked until {}", self.until)
+    }
 }
 
 impl Wallet {
@@ -57,11 +78,13 @@ impl Wallet {
         Self::initialize(connection, public_key, mode)
     }
 
-    pub fn attach(connection: Connection, mode: &Mode) -> Result<Self> {
+    pub fn attach(mut connection: Connection, mode: &Mode) -> Result<Self> {
         Self::check_magic(&connection, mode)?;
         Self::configure(&connection)?;
+        Self::upgrade(&mut connection)?;
         Ok(Self {
             connection: Mutex::new(connection),
+            unlocked: Mutex::new(None),
         })
     }
 
@@ -85,34 +108,124 @@ impl Wallet {
 
     fn set_magic(connection: &Connection, mode: &Mode) -> Result<()> {
         connection.pragma_update(None, "application_id", mode.network_magic())?;
-        connection.pragma_update(None, "user_version", 1)?;
+        connection.pragma_update(None, "user_version", SCHEMA_VERSION)?;
         Ok(())
     }
 
-    fn create_schema(connection: &Connection) -> Result<()> {
+    /**
+     * Migrations are applied all or none, a newer schema is refused rather than downgraded
+     */
+    fn upgrade(connection: &mut Connection) -> Result<()> {
+        let version: u32 = connection.query_one("PRAGMA user_version;", (), |row| row.get(0))?;
+        if version == SCHEMA_VERSION {
+            return Ok(());
+        } else if version > SCHEMA_VERSION {
+            return Err(Error::NewerSchema(version));
+        }
+        let transaction = connection.transaction()?;
+        // contract tables of version 1 were never written
+        transaction.execute("DROP TABLE htlcs;", ())?;
+        transaction.execute("DROP TABLE multisigs;", ())?;
+        transaction.execute("DROP TABLE out_leases;", ())?;
+        Self::create_contracts(&transaction)?;
+        Self::create_keystore(&transaction)?;
+        Self::create_history(&transaction)?;
+        transaction.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    // NULL height for unconfirmed
+    fn create_history(connection: &Connection) -> Result<()> {
         connection.execute(
-            "CREATE TABLE wallet(\
-                id INTEGER PRIMARY KEY CHECK (id = 0),\
-                created_at INTEGER NOT NULL,\
-                public_key BLOB NOT NULL,\
-                sequence INTEGER NOT NULL\
+            "CREATE TABLE history(\
+                hash BLOB PRIMARY KEY,\
+                kind INTEGER NOT NULL,\
+                height INTEGER,\
+                time INTEGER NOT NULL,\
+                delta INTEGER NOT NULL\
+             ) STRICT;",
+            (),
+        )?;
+        connection.execute("CREATE INDEX history_height ON history(height);", ())?;
+        Ok(())
+    }
+
+    // created and closed at height of block, NULL if open
+    fn create_contracts(connection: &Connection) -> Result<()> {
+        connection.execute(
+            "CREATE TABLE htlcs(\
+                id BLOB PRIMARY KEY,\
+                bytes BLOB NOT NULL,\
+                created INTEGER NOT NULL,\
+                closed INTEGER\
+             ) STRICT;",
+            (),
+        )?;
+        connection.execute(
+            "CREATE TABLE multisigs(\
+                id BLOB PRIMARY KEY,\
+                bytes BLOB NOT NULL,\
+                created INTEGER NOT NULL,\
+                closed INTEGER\
              ) STRICT;",
             (),
         )?;
-        connection.execute("CREATE TABLE htlcs(id BLOB PRIMARY KEY) STRICT;", ())?;
-        connection.execute("CREATE TABLE multisigs(id BLOB PRIMARY KEY) STRICT;", ())?;
         connection.execute(
             "CREATE TABLE out_leases(\
                  public_key BLOB NOT NULL,\
                  height INTEGER NOT NULL,\
-                 amount INTEGER NOT NULL\
+                 amount INTEGER NOT NULL,\
+                 created INTEGER NOT NULL,\
+                 closed INTEGER\
+             ) STRICT;",
+            (),
+        )?;
+        // last block that has been recorded
+        connection.execute(
+            "CREATE TABLE synced(\
+                id INTEGER PRIMARY KEY CHECK (id = 0),\
+                hash BLOB NOT NULL,\
+                height INTEGER NOT NULL\
+             ) STRICT;",
+            (),
+        )?;
+        Ok(())
+    }
+
+    fn create_keystore(connection: &Connection) -> Result<()> {
+        connection.execute(
+            "CREATE TABLE keystore(\
+                id INTEGER PRIMARY KEY CHECK (id = 0),\
+                salt BLOB NOT NULL,\
+                memory INTEGER NOT NULL,\
+                iterations INTEGER NOT NULL,\
+                nonce BLOB NOT NULL,\
+                cipher_text BLOB NOT NULL,\
+                tag BLOB NOT NULL\
              ) STRICT;",
             (),
         )?;
+        Ok(())
+    }
+
+    fn create_schema(connection: &Connection) -> Result<()> {
+        connection.execute(
+            "CREATE TABLE wallet(\
+                id INTEGER PRIMARY KEY CHECK (id = 0),\
+                created_at INTEGER NOT NULL,\
+                public_key BLOB NOT NULL,\
+                sequence INTEGER NOT NULL\
+             ) STRICT;",
+            (),
+        )?;
+        Self::create_contracts(connection)?;
         connection.execute(
             "CREATE TABLE transactions(id BLOB PRIMARY KEY, bytes BLOB NOT NULL) STRICT;",
             (),
         )?;
+        Self::create_keystore(connection)?;
+        Self::create_history(connection)?;
         Ok(())
     }
 
@@ -130,6 +243,7 @@ impl Wallet {
 
         Ok(Self {
             connection: Mutex::new(connection),
+            unlocked: Mutex::new(None),
         })
     }
 
@@ -154,6 +268,93 @@ impl Wallet {
         Ok(sequence)
     }
 
+    pub fn has_keystore(&self) -> Result<bool> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare_cached("SELECT EXISTS(SELECT 1 FROM keystore);")?;
+        let exists = statement.query_one((), |row| row.get(0))?;
+        Ok(exists)
+    }
+
+    pub fn keystore(&self) -> Result<EncryptedKey> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare_cached(
+            "SELECT salt, memory, iterations, nonce, cipher_text, tag FROM keystore;",
+        )?;
+        let result = statement.query_one((), |row| {
+            Ok(EncryptedKey::new(
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+                row.get(5)?,
+            ))
+        });
+        match result {
+            Ok(encrypted_key) => Ok(encrypted_key),
+            Err(SqliteError::QueryReturnedNoRows) => Err(Error::NoKeystore),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /**
+     * Replaces the stored key, e.g. to change password. The wallet is locked.
+     */
+    pub fn set_keystore(&self, encrypted_key: &EncryptedKey) -> Result<()> {
+        {
+            let connection = self.connection.lock().unwrap();
+            let mut statement = connection
+                .prepare_cached("INSERT OR REPLACE INTO keystore VALUES(?, ?, ?, ?, ?, ?, ?);")?;
+            statement.execute((
+                0,
+                encrypted_key.salt(),
+                encrypted_key.memory(),
+                encrypted_key.iterations(),
+                encrypted_key.nonce(),
+                encrypted_key.cipher_text(),
+                encrypted_key.tag(),
+            ))?;
+        }
+        self.lock();
+        Ok(())
+    }
+
+    /**
+     * Decrypts the stored key and keeps it in memory for `timeout`, that is capped at
+     * `MAX_UNLOCK_TIMEOUT`
+     */
+    pub fn unlock(&self, password: &str, timeout: Seconds) -> Result<()> {
+        let secret_key = self.keystore()?.decrypt(password)?;
+        if to_public_key(*secret_key) != self.public_key()? {
+            return Err(Error::WrongSecretKey);
+        }
+        *self.unlocked.lock().unwrap() = Some(Unlocked {
+            secret_key,
+            until: SystemClock::secs() + timeout.min(MAX_UNLOCK_TIMEOUT),
+        });
+        Ok(())
+    }
+
+    pub fn lock(&self) {
+        *self.unlocked.lock().unwrap() = None;
+    }
+
+    pub fn is_unlocked(&self) -> bool {
+        self.secret_key().is_ok()
+    }
+
+    pub fn secret_key(&self) -> Result<Zeroizing<SecretKey>> {
+        let mut unlocked = self.unlocked.lock().unwrap();
+        match &*unlocked {
+            Some(state) if SystemClock::secs() < state.until => Ok(state.secret_key.clone()),
+            Some(_) => {
+                *unlocked = None;
+                Err(Error::Locked)
+            }
+            None => Err(Error::Locked),
+        }
+    }
+
     pub fn get_transaction(&self, id: Hash) -> Result<Box<[u8]>> {
         let id: [u8; _] = id.into();
         let connection = self.connection.lock().unwrap();
@@ -171,77 +372,231 @@ impl Wallet {
         Ok(())
     }
 
-    pub fn has_htlc(&self, id: HashTimeLockContractId) -> Result<bool> {
+    /**
+     * Confirmed entry replaces unconfirmed one of the same hash
+     */
+    pub fn put_entry(&self, entry: &HistoryEntry, bytes: Option<&[u8]>) -> Result<()> {
+        let mut connection = self.connection.lock().unwrap();
+        let transaction = connection.transaction()?;
+        put_entry(&transaction, entry, bytes)?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    /**
+     * Writes what a block has changed and moves the synced block to it, all or none.
+     * Without `synced` only history of unconfirmed transactions is written.
+     */
+    pub(crate) fn commit(&self, changes: &Changes, synced: Option<(Hash, u32)>) -> Result<()> {
+        let mut connection = self.connection.lock().unwrap();
+        let transaction = connection.transaction()?;
+        for (entry, bytes) in changes.entries.iter() {
+            put_entry(&transaction, entry, bytes.as_deref())?;
+        }
+        if let Some((hash, height)) = synced {
+            for contract in changes.contracts.iter() {
+                match contract {
+                    Contract::CreateHTLC(id, htlc) => put_htlc(&transaction, *id, htlc, height)?,
+                    Contract::CloseHTLC(id) => remove_htlc(&transaction, *id, height)?,
+                    Contract::CreateMultisig(id, multisig) => {
+                        put_multisig(&transaction, *id, multisig, height)?
+                    }
+                    Contract::SpendMultisig(id) => remove_multisig(&transaction, *id, height)?,
+                    Contract::Lease(lease) => put_out_lease(&transaction, *lease, height)?,
+                    Contract::CancelLease(lease) => remove_out_lease(&transaction, *lease, height)?,
+                    Contract::WithdrawFromLease(lease, withdraw) => {
+                        withdraw_from_out_lease(&transaction, *lease, *withdraw, height)?
+                    }
+                }
+            }
+            set_synced(&transaction, hash, height)?;
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+
+    /**
+     * Last recorded block, none if the wallet predates tracking of blocks
+     */
+    pub fn synced(&self) -> Result<Option<(Hash, u32)>> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare_cached("SELECT hash, height FROM synced;")?;
+        let synced = statement
+            .query_one((), |row| {
+                let hash: [u8; 32] = row.get(0)?;
+                Ok((hash.into(), row.get(1)?))
+            })
+            .optional()?;
+        Ok(synced)
+    }
+
+    pub fn set_synced(&self, hash: Hash, height: u32) -> Result<()> {
+        let connection = self.connection.lock().unwrap();
+        set_synced(&connection, hash, height)
+    }
+
+    /**
+     * Forgets what blocks after `height` have recorded, the block at `height` becomes the synced one
+     */
+    pub fn rewind(&self, hash: Hash, height: u32) -> Result<()> {
+        let mut connection = self.connection.lock().unwrap();
+        let transaction = connection.transaction()?;
+        transaction.execute("DELETE FROM history WHERE height > ?;", (height,))?;
+        transaction.execute(
+            "DELETE FROM transactions WHERE id NOT IN (SELECT hash FROM history);",
+            (),
+        )?;
+        for table in ["htlcs", "multisigs", "out_leases"] {
+            transaction.execute(
+                &format!("DELETE FROM {table} WHERE created > ?;"),
+                (height,),
+            )?;
+            transaction.execute(
+                &format!("UPDATE {table} SET closed = NULL WHERE closed > ?;"),
+                (height,),
+            )?;
+        }
+        set_synced(&transaction, hash, height)?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    pub fn clear_unconfirmed(&self) -> Result<()> {
         let connection = self.connection.lock().unwrap();
         let mut statement =
-            connection.prepare_cached("SELECT EXISTS(SELECT 1 FROM htlcs WHERE id = ?);")?;
+            connection.prepare_cached("DELETE FROM history WHERE height IS NULL;")?;
+        statement.execute(())?;
+        Ok(())
+    }
+
+    pub fn remove_unconfirmed(&self, hashes: &[Hash]) -> Result<()> {
+        let mut connection = self.connection.lock().unwrap();
+        let transaction = connection.transaction()?;
+        {
+            let mut statement = transaction
+                .prepare_cached("DELETE FROM history WHERE hash = ? AND height IS NULL;")?;
+            for &hash in hashes {
+                let hash: [u8; _] = hash.into();
+                statement.execute((hash,))?;
+            }
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+
+    /**
+     * Unconfirmed first, then newest first
+     */
+    pub fn history(&self, offset: u32, limit: u32) -> Result<Vec<HistoryEntry>> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare_cached(
+            "SELECT hash, kind, height, time, delta FROM history \
+             ORDER BY height IS NOT NULL, height DESC, time DESC \
+             LIMIT ? OFFSET ?;",
+        )?;
+        let entries = statement
+            .query_map((limit, offset), |row| {
+                let hash: [u8; 32] = row.get(0)?;
+                Ok(HistoryEntry {
+                    hash: hash.into(),
+                    kind: row.get(1)?,
+                    height: row.get(2)?,
+                    time: Seconds::new(row.get(3)?),
+                    delta: row.get(4)?,
+                })
+            })?
+            .collect::<core::result::Result<Vec<_>, _>>()?;
+        Ok(entries)
+    }
+
+    // open one
+    pub fn has_htlc(&self, id: HashTimeLockContractId) -> Result<bool> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare_cached(
+            "SELECT EXISTS(SELECT 1 FROM htlcs WHERE id = ? AND closed IS NULL);",
+        )?;
         let exists = statement.query_one((id,), |row| row.get(0))?;
         Ok(exists)
     }
 
-    pub fn put_htlc(&self, id: HashTimeLockContractId) -> Result<()> {
+    // also closed one
+    pub fn htlc(&self, id: HashTimeLockContractId) -> Result<Option<HTLC>> {
         let connection = self.connection.lock().unwrap();
-        let mut statement = connection.prepare_cached("INSERT INTO htlcs VALUES(?);")?;
-        statement.execute((id,))?;
-        Ok(())
+        let mut statement = connection.prepare_cached("SELECT bytes FROM htlcs WHERE id = ?;")?;
+        let bytes: Option<Vec<u8>> = statement.query_one((id,), |row| row.get(0)).optional()?;
+        Ok(bytes.and_then(|bytes| from_bytes(&bytes, false).ok()))
     }
 
-    pub fn remove_htlc(&self, id: HashTimeLockContractId) -> Result<()> {
+    pub fn put_htlc(&self, id: HashTimeLockContractId, htlc: &HTLC, height: u32) -> Result<()> {
         let connection = self.connection.lock().unwrap();
-        let mut statement = connection.prepare_cached("DELETE FROM htlcs WHERE id = ?;")?;
-        statement.execute((id,))?;
-        Ok(())
+        put_htlc(&connection, id, htlc, height)
+    }
+
+    pub fn remove_htlc(&self, id: HashTimeLockContractId, height: u32) -> Result<()> {
+        let connection = self.connection.lock().unwrap();
+        remove_htlc(&connection, id, height)
     }
 
+    // open one
     pub fn has_multisig(&self, id: MultiSignatureLockContractId) -> Result<bool> {
         let connection = self.connection.lock().unwrap();
-        let mut statement =
-            connection.prepare_cached("SELECT EXISTS(SELECT 1 FROM multisigs WHERE id = ?);")?;
+        let mut statement = connection.prepare_cached(
+            "SELECT EXISTS(SELECT 1 FROM multisigs WHERE id = ? AND closed IS NULL);",
+        )?;
         let exists = statement.query_one((id,), |row| row.get(0))?;
         Ok(exists)
     }
 
-    pub fn put_multisig(&self, id: MultiSignatureLockContractId) -> Result<()> {
+    // also spent one
+    pub fn multisig(&self, id: MultiSignatureLockContractId) -> Result<Option<Multisig>> {
         let connection = self.connection.lock().unwrap();
-        let mut statement = connection.prepare_cached("INSERT INTO multisigs VALUES(?);")?;
-        statement.execute((id,))?;
-        Ok(())
+        let mut statement =
+            connection.prepare_cached("SELECT bytes FROM multisigs WHERE id = ?;")?;
+        let bytes: Option<Vec<u8>> = statement.query_one((id,), |row| row.get(0)).optional()?;
+        Ok(bytes.and_then(|bytes| from_bytes(&bytes, false).ok()))
     }
 
-    pub fn remove_multisig(&self, id: MultiSignatureLockContractId) -> Result<()> {
+    pub fn put_multisig(
+        &self,
+        id: MultiSignatureLockContractId,
+        multisig: &Multisig,
+        height: u32,
+    ) -> Result<()> {
         let connection = self.connection.lock().unwrap();
-        let mut statement = connection.prepare_cached("DELETE FROM multisigs WHERE id = ?;")?;
-        statement.execute((id,))?;
-        Ok(())
+        put_multisig(&connection, id, multisig, height)
     }
 
-    pub fn put_out_lease(&self, lease: Lease) -> Result<()> {
+    pub fn remove_multisig(&self, id: MultiSignatureLockContractId, height: u32) -> Result<()> {
         let connection = self.connection.lock().unwrap();
-        let mut statement = connection.prepare_cached("INSERT INTO out_leases VALUES(?, ?, ?);")?;
-        statement.execute((
-            lease.public_key().as_ref(),
-            lease.height(),
-            lease.balance().value(),
-        ))?;
-        Ok(())
+        remove_multisig(&connection, id, height)
     }
 
-    pub fn remove_out_lease(&self, lease: Lease) -> Result<()> {
+    pub fn out_leases(&self) -> Result<Vec<Lease>> {
         let connection = self.connection.lock().unwrap();
         let mut statement = connection.prepare_cached(
-            "DELETE FROM out_leases \
-             WHERE ROWID = (\
-                 SELECT ROWID FROM out_leases \
-                 WHERE public_key = ? AND height = ? AND amount = ? \
-                 LIMIT 1\
-             );",
+            "SELECT public_key, height, amount FROM out_leases WHERE closed IS NULL;",
         )?;
-        statement.execute((
-            lease.public_key().as_ref(),
-            lease.height(),
-            lease.balance().value(),
-        ))?;
-        Ok(())
+        let leases = statement
+            .query_map((), |row| {
+                let public_key: [u8; 32] = row.get(0)?;
+                Ok(Lease::new(
+                    public_key.into(),
+                    row.get(1)?,
+                    Amount::new(row.get(2)?),
+                ))
+            })?
+            .collect::<core::result::Result<Vec<_>, _>>()?;
+        Ok(leases)
+    }
+
+    pub fn put_out_lease(&self, lease: Lease, height: u32) -> Result<()> {
+        let connection = self.connection.lock().unwrap();
+        put_out_lease(&connection, lease, height)
+    }
+
+    pub fn remove_out_lease(&self, lease: Lease, height: u32) -> Result<()> {
+        let connection = self.connection.lock().unwrap();
+        remove_out_lease(&connection, lease, height)
     }
 
     pub fn set_out_lease_height(&self, lease: Lease, height: u32) -> Result<()> {
@@ -251,7 +606,7 @@ impl Wallet {
              SET height = ? \
              WHERE ROWID = (\
                 SELECT ROWID FROM out_leases \
-                WHERE public_key = ? AND height = ? AND amount = ? \
+                WHERE public_key = ? AND height = ? AND amount = ? AND closed IS NULL \
                 LIMIT 1\
              );",
         )?;
@@ -264,25 +619,208 @@ impl Wallet {
         Ok(())
     }
 
-    pub fn withdraw_from_out_lease(&self, lease: Lease, withdraw: Amount) -> Result<()> {
-        let connection = self.connection.lock().unwrap();
-        let mut statement = connection.prepare_cached(
-            "UPDATE out_leases \
-             SET amount = amount - ? \
-             WHERE ROWID = (\
-                SELECT ROWID FROM out_leases \
-                WHERE public_key = ? AND height = ? AND amount = ? \
-                LIMIT 1\
-             );",
-        )?;
-        statement.execute((
-            withdraw.value(),
-            lease.public_key().as_ref(),
-            lease.height(),
-            lease.balance().value(),
-        ))?;
+    pub fn withdraw_from_out_lease(
+        &self,
+        lease: Lease,
+        withdraw: Amount,
+        height: u32,
+    ) -> Result<()> {
+        let mut connection = self.connection.lock().unwrap();
+        let transaction = connection.transaction()?;
+        withdraw_from_out_lease(&transaction, lease, withdraw, height)?;
+        transaction.commit()?;
         Ok(())
     }
 }
 
+fn put_entry(connection: &Connection, entry: &HistoryEntry, bytes: Option<&[u8]>) -> Result<()> {
+    let hash: [u8; _] = entry.hash.into();
+    let mut statement =
+        connection.prepare_cached("INSERT OR REPLACE INTO history VALUES(?, ?, ?, ?, ?);")?;
+    statement.execute((
+        hash,
+        entry.kind,
+        entry.height,
+        entry.time.value(),
+        entry.delta,
+    ))?;
+    if let Some(bytes) = bytes {
+        let mut statement =
+            connection.prepare_cached("INSERT OR IGNORE INTO transactions VALUES(?, ?);")?;
+        statement.execute((hash, bytes))?;
+    }
+    Ok(())
+}
+
+fn set_synced(connection: &Connection, hash: Hash, height: u32) -> Result<()> {
+    let hash: [u8; _] = hash.into();
+    let mut statement =
+        connection.prepare_cached("INSERT OR REPLACE INTO synced VALUES(0, ?, ?);")?;
+    statement.execute((hash, height))?;
+    Ok(())
+}
+
+fn put_htlc(
+    connection: &Connection,
+    id: HashTimeLockContractId,
+    htlc: &HTLC,
+    height: u32,
+) -> Result<()> {
+    let bytes = to_bytes(htlc).expect("Serializable HTLC");
+    let mut statement = connection.prepare_cached("INSERT INTO htlcs VALUES(?, ?, ?, NULL);")?;
+    statement.execute((id, bytes, height))?;
+    Ok(())
+}
+
+fn remove_htlc(connection: &Connection, id: HashTimeLockContractId, height: u32) -> Result<()> {
+    let mut statement = connection
+        .prepare_cached("UPDATE htlcs SET closed = ? WHERE id = ? AND closed IS NULL;")?;
+    statement.execute((height, id))?;
+    Ok(())
+}
+
+fn put_multisig(
+    connection: &Connection,
+    id: MultiSignatureLockContractId,
+    multisig: &Multisig,
+    height: u32,
+) -> Result<()> {
+    let bytes = to_bytes(multisig).expect("Serializable Multisig");
+    let mut statement =
+        connection.prepare_cached("INSERT INTO multisigs VALUES(?, ?, ?, NULL);")?;
+    statement.execute((id, bytes, height))?;
+    Ok(())
+}
+
+fn remove_multisig(
+    connection: &Connection,
+    id: MultiSignatureLockContractId,
+    height: u32,
+) -> Result<()> {
+    let mut statement = connection
+        .prepare_cached("UPDATE multisigs SET closed = ? WHERE id = ? AND closed IS NULL;")?;
+    statement.execute((height, id))?;
+    Ok(())
+}
+
+fn put_out_lease(connection: &Connection, lease: Lease, height: u32) -> Result<()> {
+    let mut statement =
+        connection.prepare_cached("INSERT INTO out_leases VALUES(?, ?, ?, ?, NULL);")?;
+    statement.execute((
+        lease.public_key().as_ref(),
+        lease.height(),
+        lease.balance().value(),
+        height,
+    ))?;
+    Ok(())
+}
+
+fn remove_out_lease(connection: &Connection, lease: Lease, height: u32) -> Result<()> {
+    let mut statement = connection.prepare_cached(
+        "UPDATE out_leases \
+         SET closed = ? \
+         WHERE ROWID = (\
+             SELECT ROWID FROM out_leases \
+             WHERE public_key = ? AND height = ? AND amount = ? AND closed IS NULL \
+             LIMIT 1\
+         );",
+    )?;
+    statement.execute((
+        height,
+        lease.public_key().as_ref(),
+        lease.height(),
+        lease.balance().value(),
+    ))?;
+    Ok(())
+}
+
+// the rest of lease is a new row, so that a rewind restores the old one
+fn withdraw_from_out_lease(
+    connection: &Connection,
+    lease: Lease,
+    withdraw: Amount,
+    height: u32,
+) -> Result<()> {
+    remove_out_lease(connection, lease, height)?;
+    let rest = Lease::new(
+        lease.public_key(),
+        lease.height(),
+        lease.balance() - withdraw,
+    );
+    put_out_lease(connection, rest, height)
+}
+
+/**
+ * History and contracts of wallet that are written together with the synced block
+ */
+#[derive(Default)]
+pub(crate) struct Changes {
+    pub(crate) entries: Vec<(HistoryEntry, Option<Box<[u8]>>)>,
+    pub(crate) contracts: Vec<Contract>,
+}
+
+impl Changes {
+    pub(crate) const fn is_empty(&self) -> bool {
+        self.entries.is_empty() && self.contracts.is_empty()
+    }
+}
+
+/**
+ * Contract of wallet that a confirmed transaction has opened or closed
+ */
+pub(crate) enum Contract {
+    CreateHTLC(HashTimeLockContractId, HTLC),
+    CloseHTLC(HashTimeLockContractId),
+    CreateMultisig(MultiSignatureLockContractId, Multisig),
+    SpendMultisig(MultiSignatureLockContractId),
+    Lease(Lease),
+    CancelLease(Lease),
+    WithdrawFromLease(Lease, Amount),
+}
+
+/**
+ * Transaction or generated block, with the change of balance that it has caused
+ */
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct HistoryEntry {
+    hash: Hash,
+    kind: u8,
+    height: Option<u32>,
+    time: Seconds,
+    delta: i64,
+}
+
+impl HistoryEntry {
+    pub const fn new(hash: Hash, kind: u8, height: Option<u32>, time: Seconds, delta: i64) -> Self {
+        Self {
+            hash,
+            kind,
+            height,
+            time,
+            delta,
+        }
+    }
+
+    pub const fn hash(&self) -> Hash {
+        self.hash
+    }
+
+    pub const fn kind(&self) -> u8 {
+        self.kind
+    }
+
+    // none if unconfirmed
+    pub const fn height(&self) -> Option<u32> {
+        self.height
+    }
+
+    pub const fn time(&self) -> Seconds {
+        self.time
+    }
+
+    pub const fn delta(&self) -> i64 {
+        self.delta
+    }
+}
+
 pub type Result<T> = core::result::Result<T, Error>;
@@ -16,25 +16,38 @@
  */
 
 use crate::address::AddressCodec;
-use crate::wallet::Wallet;
-use blacknet_compat::{Mode, XDGDirectories};
+use crate::keystore::{DEFAULT_ITERATIONS, DEFAULT_MEMORY, EncryptedKey};
+use crate::scanner::Scanner;
+use crate::wallet::{HistoryEntry, Wallet};
+use argon2::Error as KdfError;
+use blacknet_compat::{Error as CompatError, Mode, XDGDirectories};
 use blacknet_kernel::blake2b::Hash;
-use blacknet_kernel::ed25519::PublicKey;
+use blacknet_kernel::ed25519::{PublicKey, SecretKey, to_public_key};
 use blacknet_log::{LogManager, Logger, error, info};
+use blacknet_time::Seconds;
 use core::error::Error as StdError;
 use core::fmt;
 use rusqlite::Error as SqliteError;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs::{DirBuilder, read_dir};
 use std::io::Error as IoError;
 #[cfg(target_family = "unix")]
 use std::os::unix::fs::DirBuilderExt;
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex, RwLock};
+use zeroize::Zeroizing;
+
+// synced block of untouched wallets is written once per this many blocks
+const SYNC_INTERVAL: u32 = 1000;
 
 pub struct WalletDB {
     logger: Logger,
     address_codec: AddressCodec,
-    wallets: HashMap<PublicKey, Wallet>,
+    dir: PathBuf,
+    wallets: RwLock<HashMap<PublicKey, Arc<Wallet>>>,
+    // skipped by connected blocks until caught up
+    detached: Mutex<HashSet<PublicKey>>,
+    anchor: Mutex<Hash>,
 }
 
 impl WalletDB {
@@ -47,17 +60,20 @@ impl WalletDB {
         info!(logger, "Driving SQLite {}", rusqlite::version());
 
         let mut wallets = HashMap::new();
-        let dir_path = Self::mkdir(dirs)?;
-        for dir_entry in read_dir(dir_path)? {
+        let dir = Self::mkdir(dirs)?;
+        for dir_entry in read_dir(&dir)? {
             let dir_entry = dir_entry?;
-            match Wallet::open(&dir_entry.path(), mode) {
+            // unconfirmed entries are recorded again when TxPool loads
+            match Wallet::open(&dir_entry.path(), mode)
+                .and_then(|wallet| wallet.clear_unconfirmed().map(|()| wallet))
+            {
                 Ok(wallet) => {
                     info!(
                         logger,
                         "Loaded wallet {}",
                         dir_entry.file_name().to_string_lossy()
                     );
-                    wallets.insert(wallet.public_key()?, wallet);
+                    wallets.insert(wallet.public_key()?, Arc::new(wallet));
                 }
                 Err(err) => {
                     error!(
@@ -73,7 +89,10 @@ impl WalletDB {
         Ok(Self {
             logger,
             address_codec: AddressCodec::new(mode)?,
-            wallets,
+            dir,
+            wallets: RwLock::new(wallets),
+            detached: Mutex::new(HashSet::new()),
+            anchor: Mutex::new(Hash::ZERO),
         })
     }
 
@@ -91,15 +110,195 @@ impl WalletDB {
         &self.address_codec
     }
 
-    pub fn sequence(&self, public_key: PublicKey) -> Result<u32, Error> {
+    pub fn public_keys(&self) -> Vec<PublicKey> {
+        self.wallets.read().unwrap().keys().copied().collect()
+    }
+
+    pub fn contains(&self, public_key: PublicKey) -> bool {
+        self.wallets.read().unwrap().contains_key(&public_key)
+    }
+
+    /**
+     * New wallet sees blocks connected after `synced` one, older ones need a rescan
+     */
+    pub fn create(
+        &self,
+        mode: &Mode,
+        public_key: PublicKey,
+        synced: (Hash, u32),
+    ) -> Result<(), Error> {
+        let mut wallets = self.wallets.write().unwrap();
+        if wallets.contains_key(&public_key) {
+            return Err(Error::WalletExists);
+        }
+        let path = self.dir.join(format!("{public_key:?}.sqlite"));
+        let wallet = Wallet::create(&path, public_key, mode)?;
+        wallet.set_synced(synced.0, synced.1)?;
+        wallets.insert(public_key, Arc::new(wallet));
+        info!(self.logger, "Created wallet {public_key:?}");
+        Ok(())
+    }
+
+    /**
+     * Scanner of wallets that follow connected blocks
+     */
+    pub fn scanner(&self) -> Scanner {
+        let detached = self.detached.lock().unwrap();
+        Scanner::new(
+            self.wallets
+                .read()
+                .unwrap()
+                .iter()
+                .filter(|(public_key, _)| !detached.contains(public_key))
+                .map(|(&public_key, wallet)| (public_key, wallet.clone()))
+                .collect(),
+        )
+    }
+
+    pub fn wallet_scanner(&self, public_key: PublicKey) -> Result<Scanner, Error> {
+        let wallet = self.wallet(public_key)?;
+        Ok(Scanner::new(HashMap::from([(public_key, wallet)])))
+    }
+
+    /**
+     * Writes what the scanner has kept for a connected block or TxPool. A wallet that fails to
+     * record a block is detached, as it would miss the block otherwise.
+     */
+    pub fn commit(&self, scanner: &mut Scanner, synced: Option<(Hash, u32)>) {
+        let all = synced.is_some_and(|(_, height)| height.is_multiple_of(SYNC_INTERVAL));
+        for (public_key, err) in scanner.commit(synced, all) {
+            error!(self.logger, "Wallet {public_key:?} error: {err}");
+            if synced.is_some() {
+                self.detach(public_key);
+            }
+        }
+    }
+
+    /**
+     * Called when block after `height` is disconnected
+     */
+    pub fn disconnect(&self, hash: Hash, height: u32) {
+        for (&public_key, wallet) in self.wallets.read().unwrap().iter() {
+            let result = wallet.synced().and_then(|synced| match synced {
+                Some((_, synced_height)) if synced_height > height => wallet.rewind(hash, height),
+                _ => Ok(()),
+            });
+            if let Err(err) = result {
+                error!(self.logger, "Wallet {public_key:?} error: {err}");
+                self.detach(public_key);
+            }
+        }
+    }
+
+    pub fn synced(&self, public_key: PublicKey) -> Result<Option<(Hash, u32)>, Error> {
+        self.wallet(public_key)?.synced()
+    }
+
+    pub fn set_synced(&self, public_key: PublicKey, hash: Hash, height: u32) -> Result<(), Error> {
+        self.wallet(public_key)?.set_synced(hash, height)
+    }
+
+    pub fn rewind(&self, public_key: PublicKey, hash: Hash, height: u32) -> Result<(), Error> {
+        self.wallet(public_key)?.rewind(hash, height)
+    }
+
+    pub fn detach(&self, public_key: PublicKey) {
+        self.detached.lock().unwrap().insert(public_key);
+    }
+
+    /**
+     * Called once the wallet has caught up with the chain
+     */
+    pub fn attach(&self, public_key: PublicKey) {
+        self.detached.lock().unwrap().remove(&public_key);
+    }
+
+    /**
+     * Called before TxPool reapplies its transactions
+     */
+    pub fn clear_unconfirmed(&self) {
+        for (public_key, wallet) in self.wallets.read().unwrap().iter() {
+            if let Err(err) = wallet.clear_unconfirmed() {
+                error!(self.logger, "Wallet {public_key:?} error: {err}");
+            }
+        }
+    }
+
+    /**
+     * Called when TxPool drops transactions
+     */
+    pub fn remove_unconfirmed(&self, hashes: &[Hash]) {
+        for (public_key, wallet) in self.wallets.read().unwrap().iter() {
+            if let Err(err) = wallet.remove_unconfirmed(hashes) {
+                error!(self.logger, "Wallet {public_key:?} error: {err}");
+            }
+        }
+    }
+
+    pub fn history(
+        &self,
+        public_key: PublicKey,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Vec<HistoryEntry>, Error> {
+        self.wallet(public_key)?.history(offset, limit)
+    }
+
+    fn wallet(&self, public_key: PublicKey) -> Result<Arc<Wallet>, Error> {
         self.wallets
+            .read()
+            .unwrap()
             .get(&public_key)
-            .ok_or(Error::UnknownWallet)?
-            .sequence()
+            .cloned()
+            .ok_or(Error::UnknownWallet)
+    }
+
+    /**
+     * Encrypts and stores the secret key of wallet, replacing the previous one if any
+     */
+    pub fn set_secret_key(&self, secret_key: SecretKey, password: &str) -> Result<(), Error> {
+        let public_key = to_public_key(secret_key);
+        let wallet = self.wallet(public_key)?;
+        let encrypted_key =
+            EncryptedKey::encrypt(&secret_key, password, DEFAULT_MEMORY, DEFAULT_ITERATIONS)?;
+        wallet.set_keystore(&encrypted_key)?;
+        info!(self.logger, "Stored encrypted key of {public_key:?}");
+        Ok(())
     }
 
+    pub fn unlock(
+        &self,
+        public_key: PublicKey,
+        password: &str,
+        timeout: Seconds,
+    ) -> Result<(), Error> {
+        self.wallet(public_key)?.unlock(password, timeout)
+    }
+
+    pub fn lock(&self, public_key: PublicKey) -> Result<(), Error> {
+        self.wallet(public_key)?.lock();
+        Ok(())
+    }
+
+    /**
+     * Requires the wallet to be unlocked
+     */
+    pub fn secret_key(&self, public_key: PublicKey) -> Result<Zeroizing<SecretKey>, Error> {
+        self.wallet(public_key)?.secret_key()
+    }
+
+    /**
+     * Recent block to reference in new transactions
+     */
     pub fn anchor(&self) -> Hash {
-        todo!();
+        *self.anchor.lock().unwrap()
+    }
+
+    /**
+     * Called by CoinDB when its rolling checkpoint moves
+     */
+    pub fn set_anchor(&self, hash: Hash) {
+        *self.anchor.lock().unwrap() = hash;
     }
 }
 
@@ -112,7 +311,15 @@ impl Drop for WalletDB {
 #[derive(Debug)]
 pub enum Error {
     UnknownWallet,
+    WalletExists,
     WrongMagic(String),
+    NewerSchema(u32),
+    NoKeystore,
+    Locked,
+    WrongPassword,
+    WrongSecretKey,
+    Entropy(CompatError),
+    Kdf(KdfError),
     Sqlite(SqliteError),
 }
 
@@ -126,9 +333,22 @@ impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::UnknownWallet => write!(f, "Requested wallet not found"),
+            Self::WalletExists => write!(f, "Wallet already exists"),
             Self::WrongMagic(name) => {
                 write!(f, "This SQLite database doesn't look like {name} wallet")
             }
+            Self::NewerSchema(version) => {
+                write!(
+                    f,
+                    "Wallet schema version {version} requires a newer software"
+                )
+            }
+            Self::NoKeystore => write!(f, "Wallet has no encrypted key"),
+            Self::Locked => write!(f, "Wallet is locked"),
+            Self::WrongPassword => write!(f, "Wrong password"),
+            Self::WrongSecretKey => write!(f, "Secret key doesn't belong to wallet"),
+            Self::Entropy(err) => write!(f, "Can't get entropy: {err}"),
+            Self::Kdf(err) => write!(f, "Can't derive key: {err}"),
             Self::Sqlite(err) => write!(f, "{err}"),
         }
     }
@@ -0,0 +1,144 @@
+/*
+ * Copyright (c) 2026 Pavel Vasin
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU Lesser General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ * GNU Lesser General Public License for more details.
+ *
+ * You should have received a copy of the GNU Lesser General Public License
+ * along with this program. If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use blacknet_compat::Mode;
+use blacknet_kernel::amount::Amount;
+use blacknet_kernel::blake2b::Hash;
+use blacknet_kernel::ed25519::PublicKey;
+use blacknet_kernel::hashlock::HashLock;
+use blacknet_kernel::htlc::HTLC;
+use blacknet_kernel::multisig::Multisig;
+use blacknet_kernel::timelock::TimeLock;
+use blacknet_kernel::transaction::{
+    ClaimHTLC, HashTimeLockContractId, MultiSignatureLockContractId, PaymentId, Transaction,
+    Transfer, TxKind,
+};
+use blacknet_serialization::format::to_bytes;
+use blacknet_time::Seconds;
+use blacknet_wallet::scanner::{Contracts, Scanner};
+use blacknet_wallet::wallet::{HistoryEntry, Wallet};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+struct Chain(HashMap<HashTimeLockContractId, HTLC>);
+
+impl Contracts for Chain {
+    fn htlc(&self, id: HashTimeLockContractId) -> Option<HTLC> {
+        self.0.get(&id).cloned()
+    }
+
+    fn multisig(&self, _id: MultiSignatureLockContractId) -> Option<Multisig> {
+        None
+    }
+}
+
+fn tx(from: PublicKey, kind: TxKind, data: &impl Serialize) -> (Transaction, Box<[u8]>) {
+    let data = to_bytes(data).unwrap().into_boxed_slice();
+    let tx = Transaction::new(from, 0, Hash::ZERO, Amount::new(1), kind, data);
+    let bytes = to_bytes(&tx).unwrap().into_boxed_slice();
+    (tx, bytes)
+}
+
+#[test]
+fn scanner() {
+    let mode = Mode::regtest();
+    let alice = PublicKey::from([1; 32]);
+    let bob = PublicKey::from([2; 32]);
+    let carol = PublicKey::from([3; 32]);
+    let wallets = [alice, bob, carol]
+        .into_iter()
+        .map(|key| (key, Arc::new(Wallet::ephemeral(key, &mode).unwrap())))
+        .collect::<HashMap<_, _>>();
+    let htlc_id = HashTimeLockContractId::default();
+    let chain = Chain(HashMap::from([(
+        htlc_id,
+        HTLC {
+            height: 1,
+            time: Seconds::new(1),
+            amount: Amount::new(7),
+            from: alice,
+            to: bob,
+            time_lock: TimeLock::new(0, 2),
+            hash_lock: HashLock::with_slice(0, &[0; 32]),
+        },
+    )]));
+    let time = Seconds::new(2);
+    let mut scanner = Scanner::new(wallets.clone());
+
+    let transfer = Transfer::new(Amount::new(10), bob, PaymentId::plain(""));
+    let (transfer, transfer_bytes) = tx(alice, TxKind::Transfer, &transfer);
+    let effects = scanner.transaction(&chain, &transfer, Hash::from([4; 32]), Some(5), time);
+    scanner.record(effects, &transfer_bytes);
+    // payout of an existing contract to a key that the transaction doesn't name
+    let claim = ClaimHTLC::new(htlc_id, [0; 32].into());
+    let (claim, claim_bytes) = tx(bob, TxKind::ClaimHTLC, &claim);
+    let effects = scanner.transaction(&chain, &claim, Hash::from([5; 32]), Some(5), time);
+    scanner.record(effects, &claim_bytes);
+    assert!(
+        scanner
+            .commit(Some((Hash::from([6; 32]), 5)), false)
+            .is_empty()
+    );
+
+    let entry =
+        |hash: u8, delta| HistoryEntry::new(Hash::from([hash; 32]), 0, Some(5), time, delta);
+    let alice_history = wallets[&alice].history(0, 10).unwrap();
+    let bob_history = wallets[&bob].history(0, 10).unwrap();
+    assert!(alice_history.contains(&entry(4, -11)));
+    assert!(
+        alice_history
+            .iter()
+            .any(|e| e.hash() == Hash::from([5; 32]) && e.delta() == 0)
+    );
+    assert!(bob_history.contains(&entry(4, 10)));
+    assert!(
+        bob_history
+            .iter()
+            .any(|e| e.hash() == Hash::from([5; 32]) && e.delta() == 6)
+    );
+    assert_eq!(
+        *wallets[&bob].get_transaction(Hash::from([5; 32])).unwrap(),
+        *claim_bytes
+    );
+    assert_eq!(
+        wallets[&alice].synced().unwrap(),
+        Some((Hash::from([6; 32]), 5))
+    );
+    // untouched wallet lags behind until all are synced
+    assert_eq!(wallets[&carol].synced().unwrap(), None);
+    assert!(
+        scanner
+            .commit(Some((Hash::from([7; 32]), 6)), true)
+            .is_empty()
+    );
+    assert_eq!(
+        wallets[&carol].synced().unwrap(),
+        Some((Hash::from([7; 32]), 6))
+    );
+    assert!(wallets[&carol].history(0, 10).unwrap().is_empty());
+
+    // unconfirmed transaction has no contracts and leaves synced block as it was
+    let effects = scanner.transaction(&chain, &transfer, Hash::from([8; 32]), None, time);
+    scanner.record(effects, &transfer_bytes);
+    assert!(scanner.commit(None, true).is_empty());
+    assert_eq!(wallets[&bob].history(0, 1).unwrap()[0].height(), None);
+    assert_eq!(
+        wallets[&bob].synced().unwrap(),
+        Some((Hash::from([7; 32]), 6))
+    );
+}
@@ -19,12 +19,18 @@ use blacknet_compat::Mode;
 use blacknet_kernel::account::Lease;
 use blacknet_kernel::amount::Amount;
 use blacknet_kernel::blake2b::Hash;
-use blacknet_kernel::ed25519::PublicKey;
+use blacknet_kernel::ed25519::{PublicKey, SecretKey, to_public_key};
+use blacknet_kernel::hashlock::HashLock;
+use blacknet_kernel::htlc::HTLC;
+use blacknet_kernel::multisig::{Deposit, Multisig};
+use blacknet_kernel::timelock::TimeLock;
 use blacknet_kernel::transaction::{HashTimeLockContractId, MultiSignatureLockContractId};
-use blacknet_wallet::wallet::Wallet;
+use blacknet_time::Seconds;
+use blacknet_wallet::keystore::EncryptedKey;
+use blacknet_wallet::wallet::{HistoryEntry, Wallet};
 use blacknet_wallet::walletdb::Error;
 use core::assert_matches;
-use rusqlite::Connection;
+use rusqlite::{Connection, OpenFlags};
 
 #[test]
 fn ephemeral() {
@@ -43,15 +49,129 @@ fn magic() {
     assert_matches!(Wallet::attach(connection, &mode), Err(Error::WrongMagic(_)));
 }
 
+#[test]
+fn schema() {
+    let mode = Mode::regtest();
+    let connection = Connection::open_in_memory().unwrap();
+    connection
+        .pragma_update(None, "application_id", mode.network_magic())
+        .unwrap();
+    connection
+        .pragma_update(None, "user_version", 1000)
+        .unwrap();
+    assert_matches!(
+        Wallet::attach(connection, &mode),
+        Err(Error::NewerSchema(1000))
+    );
+
+    // version 1 had neither keystore nor history, nor blocks that it was synced with
+    let connection = Connection::open_in_memory().unwrap();
+    version_1(&connection, &mode);
+    let wallet = Wallet::attach(connection, &mode).unwrap();
+    assert_matches!(wallet.has_keystore(), Ok(false));
+    assert_matches!(wallet.history(0, 10).as_deref(), Ok([]));
+    assert_matches!(wallet.synced(), Ok(None));
+    assert!(wallet.out_leases().unwrap().is_empty());
+    assert_eq!(wallet.public_key().unwrap(), PublicKey::default());
+
+    // a migration that fails after dropping and creating tables leaves the schema as it was
+    let uri = "file:schema?mode=memory&cache=shared";
+    let flags = OpenFlags::default() | OpenFlags::SQLITE_OPEN_URI;
+    let connection = Connection::open_with_flags(uri, flags).unwrap();
+    version_1(&connection, &mode);
+    connection
+        .execute("CREATE TABLE history(hash BLOB);", ())
+        .unwrap();
+    let migrated = Connection::open_with_flags(uri, flags).unwrap();
+    assert_matches!(Wallet::attach(migrated, &mode), Err(Error::Sqlite(_)));
+    let version: u32 = connection
+        .query_one("PRAGMA user_version;", (), |row| row.get(0))
+        .unwrap();
+    let tables = connection
+        .prepare("SELECT name FROM sqlite_schema WHERE type = 'table' ORDER BY name;")
+        .unwrap()
+        .query_map((), |row| row.get::<_, String>(0))
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(version, 1);
+    assert_eq!(
+        tables,
+        [
+            "history",
+            "htlcs",
+            "multisigs",
+            "out_leases",
+            "transactions",
+            "wallet"
+        ]
+    );
+}
+
+fn version_1(connection: &Connection, mode: &Mode) {
+    connection
+        .pragma_update(None, "application_id", mode.network_magic())
+        .unwrap();
+    connection.pragma_update(None, "user_version", 1).unwrap();
+    connection
+        .execute_batch(
+            "CREATE TABLE wallet(\
+                id INTEGER PRIMARY KEY CHECK (id = 0),\
+                created_at INTEGER NOT NULL,\
+                public_key BLOB NOT NULL,\
+                sequence INTEGER NOT NULL\
+             ) STRICT;\
+             CREATE TABLE htlcs(id BLOB PRIMARY KEY) STRICT;\
+             CREATE TABLE multisigs(id BLOB PRIMARY KEY) STRICT;\
+             CREATE TABLE out_leases(\
+                 public_key BLOB NOT NULL,\
+                 height INTEGER NOT NULL,\
+                 amount INTEGER NOT NULL\
+             ) STRICT;\
+             CREATE TABLE transactions(id BLOB PRIMARY KEY, bytes BLOB NOT NULL) STRICT;",
+        )
+        .unwrap();
+    connection
+        .execute(
+            "INSERT INTO wallet VALUES(0, 1, ?, 0);",
+            (PublicKey::default().as_ref(),),
+        )
+        .unwrap();
+}
+
+fn new_htlc(amount: u64) -> HTLC {
+    HTLC {
+        height: 1,
+        time: Seconds::new(1),
+        amount: Amount::new(amount),
+        from: PublicKey::default(),
+        to: PublicKey::default(),
+        time_lock: TimeLock::new(0, 2),
+        hash_lock: HashLock::with_slice(0, &[0; 32]),
+    }
+}
+
+fn new_multisig(amount: u64) -> Multisig {
+    Multisig::new(
+        1,
+        [Deposit::new(PublicKey::default(), Amount::new(amount))].into(),
+    )
+}
+
 #[test]
 fn htlc() {
     let mode = Mode::regtest();
     let wallet = Wallet::ephemeral(PublicKey::default(), &mode).unwrap();
     let htlc_id = HashTimeLockContractId::default();
-    assert_matches!(wallet.put_htlc(htlc_id), Ok(()));
+    assert_matches!(wallet.put_htlc(htlc_id, &new_htlc(5), 1), Ok(()));
     assert_matches!(wallet.has_htlc(htlc_id), Ok(true));
-    assert_matches!(wallet.remove_htlc(htlc_id), Ok(()));
+    assert_matches!(wallet.remove_htlc(htlc_id, 2), Ok(()));
     assert_matches!(wallet.has_htlc(htlc_id), Ok(false));
+    // closed one is still known for payouts
+    assert_eq!(
+        wallet.htlc(htlc_id).unwrap().unwrap().amount,
+        Amount::new(5)
+    );
 }
 
 #[test]
@@ -59,10 +179,17 @@ fn multisig() {
     let mode = Mode::regtest();
     let wallet = Wallet::ephemeral(PublicKey::default(), &mode).unwrap();
     let multisig_id = MultiSignatureLockContractId::default();
-    assert_matches!(wallet.put_multisig(multisig_id), Ok(()));
+    assert_matches!(
+        wallet.put_multisig(multisig_id, &new_multisig(5), 1),
+        Ok(())
+    );
     assert_matches!(wallet.has_multisig(multisig_id), Ok(true));
-    assert_matches!(wallet.remove_multisig(multisig_id), Ok(()));
+    assert_matches!(wallet.remove_multisig(multisig_id, 2), Ok(()));
     assert_matches!(wallet.has_multisig(multisig_id), Ok(false));
+    assert_eq!(
+        wallet.multisig(multisig_id).unwrap().unwrap().amount(),
+        Amount::new(5)
+    );
 }
 
 #[test]
@@ -72,13 +199,15 @@ fn out_lease() {
     let lease1 = Lease::new(PublicKey::default(), 1, Amount::new(123));
     let lease2 = Lease::new(PublicKey::default(), 2, Amount::new(123));
     let lease3 = Lease::new(PublicKey::default(), 2, Amount::new(100));
-    assert_matches!(wallet.put_out_lease(lease1), Ok(()));
+    assert_matches!(wallet.put_out_lease(lease1, 1), Ok(()));
     assert_matches!(wallet.set_out_lease_height(lease1, lease2.height()), Ok(()));
     assert_matches!(
-        wallet.withdraw_from_out_lease(lease2, Amount::new(23)),
+        wallet.withdraw_from_out_lease(lease2, Amount::new(23), 3),
         Ok(())
     );
-    assert_matches!(wallet.remove_out_lease(lease3), Ok(()));
+    assert!(wallet.out_leases().unwrap() == [lease3]);
+    assert_matches!(wallet.remove_out_lease(lease3, 4), Ok(()));
+    assert!(wallet.out_leases().unwrap().is_empty());
 }
 
 #[test]
@@ -91,3 +220,130 @@ fn transaction() {
     let bytes = wallet.get_transaction(tx_id).unwrap();
     assert_eq!(tx_bytes, *bytes);
 }
+
+#[test]
+fn keystore() {
+    let mode = Mode::regtest();
+    let secret_key = SecretKey::from([1; 32]);
+    let wallet = Wallet::ephemeral(to_public_key(secret_key), &mode).unwrap();
+    assert_matches!(wallet.has_keystore(), Ok(false));
+    assert_matches!(
+        wallet.unlock("password", Seconds::new(60)),
+        Err(Error::NoKeystore)
+    );
+
+    let encrypted_key = EncryptedKey::encrypt(&secret_key, "password", 8, 1).unwrap();
+    assert_matches!(wallet.set_keystore(&encrypted_key), Ok(()));
+    assert_eq!(wallet.keystore().unwrap(), encrypted_key);
+    assert!(matches!(wallet.secret_key(), Err(Error::Locked)));
+    assert_matches!(
+        wallet.unlock("wrong", Seconds::new(60)),
+        Err(Error::WrongPassword)
+    );
+    assert_matches!(wallet.unlock("password", Seconds::new(60)), Ok(()));
+    assert_eq!(wallet.secret_key().unwrap().as_ref(), secret_key.as_ref());
+    wallet.lock();
+    assert!(!wallet.is_unlocked());
+    assert_matches!(wallet.unlock("password", Seconds::new(0)), Ok(()));
+    assert!(matches!(wallet.secret_key(), Err(Error::Locked)));
+    assert_matches!(wallet.unlock("password", Seconds::new(i64::MAX)), Ok(()));
+    assert!(wallet.is_unlocked());
+    wallet.lock();
+
+    let mut tag = *encrypted_key.tag();
+    tag[0] ^= 1;
+    let tampered = EncryptedKey::new(
+        *encrypted_key.salt(),
+        encrypted_key.memory(),
+        encrypted_key.iterations(),
+        *encrypted_key.nonce(),
+        *encrypted_key.cipher_text(),
+        tag,
+    );
+    assert!(matches!(
+        tampered.decrypt("password"),
+        Err(Error::WrongPassword)
+    ));
+    let weak = EncryptedKey::new(
+        *encrypted_key.salt(),
+        0,
+        0,
+        *encrypted_key.nonce(),
+        *encrypted_key.cipher_text(),
+        *encrypted_key.tag(),
+    );
+    assert!(matches!(weak.decrypt("password"), Err(Error::Kdf(_))));
+
+    let other = Wallet::ephemeral(PublicKey::default(), &mode).unwrap();
+    assert_matches!(other.set_keystore(&encrypted_key), Ok(()));
+    assert_matches!(
+        other.unlock("password", Seconds::new(60)),
+        Err(Error::WrongSecretKey)
+    );
+}
+
+#[test]
+fn history() {
+    let mode = Mode::regtest();
+    let wallet = Wallet::ephemeral(PublicKey::default(), &mode).unwrap();
+    let time = Seconds::new(1);
+    let generated = HistoryEntry::new(Hash::from([1; 32]), 254, Some(1), time, 100);
+    let spent = HistoryEntry::new(Hash::from([2; 32]), 0, Some(2), time, -30);
+    let pending = HistoryEntry::new(Hash::from([3; 32]), 0, None, time, 5);
+    assert_matches!(wallet.put_entry(&generated, None), Ok(()));
+    assert_matches!(wallet.put_entry(&spent, Some(&[1, 2, 3])), Ok(()));
+    assert_matches!(wallet.put_entry(&pending, None), Ok(()));
+    let history = wallet.history(0, 10).unwrap();
+    assert_eq!(
+        history.iter().map(HistoryEntry::hash).collect::<Vec<_>>(),
+        [pending.hash(), spent.hash(), generated.hash()]
+    );
+
+    assert_matches!(wallet.remove_unconfirmed(&[spent.hash()]), Ok(()));
+    assert_eq!(wallet.history(0, 10).unwrap().len(), 3);
+    assert_matches!(wallet.remove_unconfirmed(&[pending.hash()]), Ok(()));
+    assert_eq!(wallet.history(0, 10).unwrap().len(), 2);
+
+    assert_matches!(wallet.put_entry(&pending, None), Ok(()));
+    assert_matches!(wallet.rewind(generated.hash(), 1), Ok(()));
+    assert_matches!(wallet.clear_unconfirmed(), Ok(()));
+    assert_eq!(wallet.history(0, 10).unwrap(), [generated]);
+}
+
+#[test]
+fn rewind() {
+    let mode = Mode::regtest();
+    let wallet = Wallet::ephemeral(PublicKey::default(), &mode).unwrap();
+    let time = Seconds::new(1);
+    let kept = HistoryEntry::new(Hash::from([1; 32]), 0, Some(1), time, 10);
+    let forgotten = HistoryEntry::new(Hash::from([2; 32]), 0, Some(3), time, 20);
+    let pending = HistoryEntry::new(Hash::from([3; 32]), 0, None, time, -5);
+    assert_matches!(wallet.put_entry(&kept, Some(&[1])), Ok(()));
+    assert_matches!(wallet.put_entry(&forgotten, Some(&[2])), Ok(()));
+    assert_matches!(wallet.put_entry(&pending, Some(&[3])), Ok(()));
+    let htlc_id = HashTimeLockContractId::default();
+    let multisig_id = MultiSignatureLockContractId::default();
+    let lease = Lease::new(PublicKey::default(), 1, Amount::new(100));
+    assert_matches!(wallet.put_htlc(htlc_id, &new_htlc(5), 1), Ok(()));
+    assert_matches!(wallet.remove_htlc(htlc_id, 3), Ok(()));
+    assert_matches!(
+        wallet.put_multisig(multisig_id, &new_multisig(5), 3),
+        Ok(())
+    );
+    assert_matches!(wallet.put_out_lease(lease, 1), Ok(()));
+    assert_matches!(
+        wallet.withdraw_from_out_lease(lease, Amount::new(10), 3),
+        Ok(())
+    );
+    assert_matches!(wallet.set_synced(Hash::from([3; 32]), 3), Ok(()));
+
+    assert_matches!(wallet.rewind(Hash::from([2; 32]), 2), Ok(()));
+    assert_eq!(wallet.synced().unwrap(), Some((Hash::from([2; 32]), 2)));
+    assert_eq!(wallet.history(0, 10).unwrap(), [pending, kept]);
+    assert_matches!(wallet.get_transaction(forgotten.hash()), Err(_));
+    assert_eq!(*wallet.get_transaction(kept.hash()).unwrap(), [1]);
+    assert_eq!(*wallet.get_transaction(pending.hash()).unwrap(), [3]);
+    assert_matches!(wallet.has_htlc(htlc_id), Ok(true));
+    assert!(wallet.multisig(multisig_id).unwrap().is_none());
+    assert!(wallet.out_leases().unwrap() == [lease]);
+}